@@ -0,0 +1,87 @@
+use chrono::{Datelike, Months, NaiveDate};
+use std::collections::BTreeMap;
+
+/// Width of a single day cell, including the separating space.
+const CELL_WIDTH: usize = 4;
+
+/// Renders a text calendar of the given year with a photo count in every day cell.
+///
+/// Each month is printed as a grid with one row per week (starting on Monday).
+/// Days with photos show their count, days without photos show a dot.
+///
+/// # Arguments
+///
+/// * `year` - The year to render
+/// * `counts` - Number of photos per day, days missing from the map have no photos
+///
+/// # Examples
+///
+/// ```text
+/// 2025-05
+///   Mo  Tu  We  Th  Fr  Sa  Su
+///                2   .   .   .
+///    .   .   .   .   .   .   .
+/// ```
+pub fn render_year(year: i32, counts: &BTreeMap<NaiveDate, usize>) -> String {
+    (1..=12)
+        .filter_map(|month| NaiveDate::from_ymd_opt(year, month, 1))
+        .map(|first| render_month(first, counts))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Renders a single month starting at `first`, see [`render_year`].
+fn render_month(first: NaiveDate, counts: &BTreeMap<NaiveDate, usize>) -> String {
+    let mut ret = first.format("%Y-%m\n").to_string();
+    ret += &["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"]
+        .map(|day| format!("{day:>CELL_WIDTH$}"))
+        .concat();
+    ret.push('\n');
+    let offset = first.weekday().num_days_from_monday() as usize;
+    ret += &" ".repeat(offset * CELL_WIDTH);
+    let next_month = first + Months::new(1);
+    for day in first.iter_days().take_while(|day| *day < next_month) {
+        let cell = counts
+            .get(&day)
+            .map_or(".".to_string(), |count| count.to_string());
+        ret += &format!("{cell:>CELL_WIDTH$}");
+        if day.weekday().num_days_from_monday() == 6 {
+            ret.push('\n');
+        }
+    }
+    if !ret.ends_with('\n') {
+        ret.push('\n');
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_month() {
+        let may = NaiveDate::from_ymd_opt(2025, 5, 1).unwrap();
+        let counts = BTreeMap::from([
+            (may, 2),
+            (NaiveDate::from_ymd_opt(2025, 5, 31).unwrap(), 15),
+        ]);
+        let rendered = super::render_month(may, &counts);
+        let lines = rendered.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "2025-05");
+        assert_eq!(lines[1], "  Mo  Tu  We  Th  Fr  Sa  Su");
+        assert_eq!(lines[2], "               2   .   .   .");
+        assert_eq!(lines[3], "   .   .   .   .   .   .   .");
+        assert_eq!(lines[6], "   .   .   .   .   .  15");
+        assert_eq!(lines.len(), 7);
+    }
+
+    #[test]
+    fn render_year() {
+        let rendered = super::render_year(2025, &BTreeMap::new());
+        assert_eq!(rendered.matches("2025-").count(), 12);
+        assert!(rendered.starts_with("2025-01\n"));
+        assert!(rendered.contains("\n2025-12\n"));
+    }
+}
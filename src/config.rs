@@ -0,0 +1,111 @@
+use crate::name_template::NameTemplate;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the configuration file looked up in the target directory and its ancestors.
+pub const CONFIG_NAME: &str = ".photo-dater.toml";
+
+/// Per-directory defaults read from a [`CONFIG_NAME`] file, e.g.
+///
+/// ```toml
+/// no-recursive = true
+/// extensions = ["jpg", "cr2"]
+/// name-template = "{name}_{index}{ext}"
+/// ```
+///
+/// Options given on the command line take precedence over the file.
+#[derive(Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Default of `--no-recursive`
+    pub no_recursive: Option<bool>,
+    /// Default of `--ext`, used when no extension is given on the command line
+    pub extensions: Option<Vec<String>>,
+    /// Default of `--name-template` of `files-rename`
+    #[serde(default, deserialize_with = "deserialize_template")]
+    pub name_template: Option<NameTemplate>,
+}
+
+fn deserialize_template<'de, D>(deserializer: D) -> Result<Option<NameTemplate>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+}
+
+impl Config {
+    /// Reads the configuration file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not valid TOML or contains
+    /// unknown options.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)?;
+        toml::from_str(&content).context(format!("Invalid config file {path:?}"))
+    }
+
+    /// Finds the nearest [`CONFIG_NAME`] file in `directory` or its ancestors.
+    pub fn find(directory: impl AsRef<Path>) -> Result<Option<PathBuf>> {
+        Ok(std::path::absolute(directory)?
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_NAME))
+            .find(|path| path.is_file()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(CONFIG_NAME);
+
+        fs::write(
+            &path,
+            "no-recursive = true\nextensions = [\"jpg\"]\nname-template = \"{name}_{index}{ext}\"\n",
+        )?;
+        assert_eq!(
+            Config::read(&path)?,
+            Config {
+                no_recursive: Some(true),
+                extensions: Some(vec!["jpg".to_string()]),
+                name_template: Some("{name}_{index}{ext}".parse()?),
+            }
+        );
+
+        fs::write(&path, "")?;
+        assert_eq!(Config::read(&path)?, Config::default());
+
+        fs::write(&path, "recursive = false\n")?;
+        assert!(Config::read(&path).is_err());
+        fs::write(&path, "name-template = \"{i}\"\n")?;
+        assert!(Config::read(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn find() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let nested = dir.path().join("a/b");
+        fs::create_dir_all(&nested)?;
+        assert_eq!(Config::find(&nested)?, None);
+
+        fs::write(dir.path().join(CONFIG_NAME), "")?;
+        assert_eq!(Config::find(&nested)?, Some(dir.path().join(CONFIG_NAME)));
+
+        fs::write(nested.join(CONFIG_NAME), "")?;
+        assert_eq!(Config::find(&nested)?, Some(nested.join(CONFIG_NAME)));
+        Ok(())
+    }
+}
@@ -1,10 +1,18 @@
-use crate::files::Files;
-use crate::files_interval::FilesInterval;
-use anyhow::{anyhow, Context, Result};
-use std::path::PathBuf;
+use crate::error::PhotoDaterError;
+use crate::files::{Files, ReadOptions};
+use crate::files_interval::{FilesInterval, NameFormat};
+use crate::name_template::DirectoryTemplate;
+use serde::Serialize;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Files added by file managers, which do not keep a directory from being empty.
+const IGNORABLE_FILES: &[&str] = &[".DS_Store", "Thumbs.db", "desktop.ini"];
 
 /// Status of a directory's name relative to its file contents' date range.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
 pub enum NameStatus {
     /// Directory name exactly matches the date range of contained files
     Valid,
@@ -32,11 +40,12 @@ impl Directory {
     /// Creates a new Directory instance from the given path.
     ///
     /// This constructor validates that the path is actually a directory and
-    /// recursively reads all files contained within it.
+    /// reads all files contained within it using the given options.
     ///
     /// # Arguments
     ///
     /// * `directory` - Path to the directory to analyze
+    /// * `options` - Options controlling how files are read (see [`Files::read`])
     ///
     /// # Errors
     ///
@@ -44,27 +53,34 @@ impl Directory {
     /// - The provided path is not a directory
     /// - The directory cannot be read due to permissions or I/O errors
     /// - Files within the directory cannot be processed
-    pub fn try_from(directory: PathBuf) -> Result<Self> {
+    pub fn try_from(directory: PathBuf, options: &ReadOptions) -> Result<Self, PhotoDaterError> {
         if !directory.is_dir() {
-            return Err(anyhow!("{:?} is not directory", directory));
+            return Err(PhotoDaterError::NotADirectory(directory));
         }
         Ok(Directory {
-            files: Files::read(&directory)?,
+            files: Files::read(&directory, options)?,
             directory,
         })
     }
 
+    /// Creates a Directory instance of files that were read elsewhere, e.g. with
+    /// [`Files::from_paths`], without checking that `directory` exists.
+    pub fn from_files(directory: PathBuf, files: Files) -> Self {
+        Directory { directory, files }
+    }
+
     /// Extracts the directory name as a string slice.
     ///
     /// # Errors
     ///
     /// Returns an error if the directory path has no filename component
     /// or if the filename is not valid UTF-8.
-    pub fn name(&self) -> Result<&str> {
+    pub fn name(&self) -> Result<&str, PhotoDaterError> {
         self.directory
             .file_name()
-            .and_then(|s| s.to_str())
-            .context("Invalid directory name")
+            .ok_or_else(|| PhotoDaterError::NoFileName(self.directory.clone()))?
+            .to_str()
+            .ok_or_else(|| PhotoDaterError::NonUtf8Name(self.directory.clone()))
     }
 
     /// Calculates the date interval spanned by files in this directory.
@@ -72,8 +88,10 @@ impl Directory {
     /// # Errors
     ///
     /// Returns an error if the directory contains no files with valid dates.
-    fn interval(&self) -> Result<FilesInterval> {
-        self.files.interval().context("Does not get interval")
+    fn interval(&self) -> Result<FilesInterval, PhotoDaterError> {
+        self.files
+            .interval()
+            .ok_or(PhotoDaterError::NoFilesWithDates)
     }
 
     /// This method compares a directory name against a file date interval to
@@ -83,14 +101,23 @@ impl Directory {
     ///
     /// * `interval` - The actual date range of files in the directory
     /// * `name` - The directory name to evaluate
-    fn get_status(interval: &FilesInterval, name: &str) -> NameStatus {
-        match FilesInterval::try_from_name(name) {
+    /// * `format` - Format of dates in the directory name
+    fn get_status(interval: &FilesInterval, name: &str, format: &NameFormat) -> NameStatus {
+        Self::compare(
+            interval,
+            FilesInterval::try_from_name(name, format).as_ref(),
+        )
+    }
+
+    /// Compares the `named` interval found in a directory name with the actual `interval`.
+    fn compare(interval: &FilesInterval, named: Option<&FilesInterval>) -> NameStatus {
+        match named {
             Some(FilesInterval { from, to })
                 if from.date() == interval.from.date() && to.date() == interval.to.date() =>
             {
                 NameStatus::Valid
             }
-            Some(FilesInterval { from, to }) if from <= interval.from && to >= interval.to => {
+            Some(FilesInterval { from, to }) if *from <= interval.from && *to >= interval.to => {
                 NameStatus::SuperSet
             }
             Some(_) => NameStatus::Invalid,
@@ -100,12 +127,33 @@ impl Directory {
 
     /// Evaluates the current directory name against its file contents.
     ///
+    /// Dates in the directory name are expected in the given `format`.
+    ///
     /// # Errors
     ///
     /// Returns an error if the directory name cannot be extracted or if
     /// the file date interval cannot be determined.
-    pub fn name_status(&self) -> Result<NameStatus> {
-        Ok(Self::get_status(&self.interval()?, self.name()?))
+    pub fn name_status(&self, format: &NameFormat) -> Result<NameStatus, PhotoDaterError> {
+        Ok(Self::get_status(&self.interval()?, self.name()?, format))
+    }
+
+    /// Evaluates the current directory name like [`Directory::name_status`], also
+    /// returning the intervals it compared.
+    ///
+    /// # Returns
+    ///
+    /// The status, the interval found in the name (if any) and the interval of the files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`Directory::name_status`].
+    pub fn name_intervals(
+        &self,
+        format: &NameFormat,
+    ) -> Result<(NameStatus, Option<FilesInterval>, FilesInterval), PhotoDaterError> {
+        let interval = self.interval()?;
+        let named = FilesInterval::try_from_name(self.name()?, format);
+        Ok((Self::compare(&interval, named.as_ref()), named, interval))
     }
 
     /// This method analyzes the current directory name and file date range to
@@ -114,6 +162,9 @@ impl Directory {
     /// # Arguments
     ///
     /// * `max_interval` - Maximum allowed interval in days between oldest and newest files
+    /// * `format` - Format of dates in the current and the suggested name
+    /// * `template` - Layout of the suggested name. Without a template the interval is
+    ///   searched anywhere in the current name and put at the start of the suggested one.
     ///
     /// # Errors
     ///
@@ -121,47 +172,286 @@ impl Directory {
     /// - The date interval exceeds the maximum allowed interval
     /// - The directory name cannot be extracted or is not valid UTF-8
     /// - The file date interval cannot be determined
-    pub fn rename(&self, max_interval: u32) -> Result<(NameStatus, PathBuf)> {
-        let interval = self.interval()?;
-        let delta = self.interval()?.delta();
-        if delta.abs().num_days() > max_interval.into() {
-            return Err(anyhow!(
-                "Interval from {} to {} is too large ({} days)",
-                interval.from,
-                interval.to,
-                delta.num_days()
-            ));
-        }
-        let old_name = self
-            .directory
-            .file_name()
-            .ok_or(anyhow!("Cannot get filename from {:?}", self.directory))?
-            .to_str()
-            .ok_or(anyhow!(
-                "File name {:?} is not UTF-8 valid string",
-                self.directory
-            ))?;
-        let status = Self::get_status(&interval, old_name);
+    pub fn rename(
+        &self,
+        max_interval: u32,
+        format: &NameFormat,
+        template: Option<&DirectoryTemplate>,
+    ) -> Result<(NameStatus, PathBuf), PhotoDaterError> {
+        let interval = self.checked_interval(max_interval)?;
+        let name = self.name()?;
+        let status = match template {
+            Some(template) => Self::compare(
+                &interval,
+                template
+                    .split(name, format)
+                    .map(|(named, _)| named)
+                    .as_ref(),
+            ),
+            None => Self::get_status(&interval, name, format),
+        };
         Ok((
             status,
             match status {
-                NameStatus::Valid => self.directory.clone(),
-                // TODO how to solve invalid dates???
-                NameStatus::Invalid => self
-                    .directory
-                    .with_file_name(format!("{interval} {old_name}")),
-                NameStatus::SuperSet => self.directory.clone(),
-                NameStatus::None => self
-                    .directory
-                    .with_file_name(format!("{interval} {old_name}")),
+                NameStatus::Valid | NameStatus::SuperSet => self.directory.clone(),
+                NameStatus::Invalid | NameStatus::None => {
+                    self.with_interval(&interval, format, template)?
+                }
             },
         ))
     }
 
+    /// Suggests a new name with the date replaced by the date interval of the files,
+    /// e.g. `2025-05-01 Trip` becomes `2025-05-01 - 03 Trip` after adding photos from
+    /// the following days. A date found later in the name is moved to the start like
+    /// by [`Directory::rename`]. Names without a date get the interval prepended and
+    /// names whose date already matches the files, see [`Directory::name_status`],
+    /// are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_interval` - Maximum allowed interval in days between oldest and newest files
+    /// * `format` - Format of dates in the current and the suggested name
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for the same reasons as [`Directory::rename`].
+    pub fn rename_interval(
+        &self,
+        max_interval: u32,
+        format: &NameFormat,
+    ) -> Result<PathBuf, PhotoDaterError> {
+        let interval = self.checked_interval(max_interval)?;
+        // Names already matching the files are left alone, see `Directory::name_status`
+        if Self::get_status(&interval, self.name()?, format) == NameStatus::Valid {
+            return Ok(self.directory.clone());
+        }
+        self.with_interval(&interval, format, None)
+    }
+
+    /// Returns the interval of the files, failing if it spans more than `max_interval` days.
+    fn checked_interval(&self, max_interval: u32) -> Result<FilesInterval, PhotoDaterError> {
+        let interval = self.interval()?;
+        let days = interval.delta_days();
+        if days.abs() > max_interval.into() {
+            return Err(PhotoDaterError::IntervalTooLarge {
+                from: interval.from,
+                to: interval.to,
+                days,
+                max: max_interval,
+            });
+        }
+        Ok(interval)
+    }
+
+    /// Returns the path of the directory named by `interval` followed by the current
+    /// name without its date, which is searched anywhere in the name, see
+    /// [`FilesInterval::try_find`].
+    ///
+    /// With a `template`, the name is laid out by it instead. The descriptive part is
+    /// the current name without the interval placed by the template, or without its
+    /// date if the name does not follow the template.
+    fn with_interval(
+        &self,
+        interval: &FilesInterval,
+        format: &NameFormat,
+        template: Option<&DirectoryTemplate>,
+    ) -> Result<PathBuf, PhotoDaterError> {
+        let name = self.name()?;
+        let without_date = || {
+            FilesInterval::try_find(name, format).map(|(before, _, after)| match (before, after) {
+                ("", rest) | (rest, "") => rest.to_string(),
+                (before, after) => format!("{before} {after}"),
+            })
+        };
+        let interval = interval.format(format);
+        Ok(self.directory.with_file_name(match template {
+            Some(template) => {
+                let name = template
+                    .split(name, format)
+                    .map(|(_, name)| name.to_string())
+                    .or_else(without_date)
+                    .unwrap_or_else(|| name.to_string());
+                template.render(&name, &interval)
+            }
+            None => match without_date().unwrap_or_else(|| name.to_string()).as_str() {
+                "" => interval,
+                name => format!("{interval} {name}"),
+            },
+        }))
+    }
+
     /// Provides read-only access to the files contained in this directory.
     pub fn get_files(&self) -> &Files {
         &self.files
     }
+
+    /// Consumes the directory, returning its files.
+    pub fn into_files(self) -> Files {
+        self.files
+    }
+
+    /// Provides mutable access to the files contained in this directory.
+    pub fn files_mut(&mut self) -> &mut Files {
+        &mut self.files
+    }
+
+    /// Finds immediate subdirectories named by a date (e.g. `2025-05-01` created by
+    /// `move-by-days`) that are empty or contain only [`IGNORABLE_FILES`].
+    ///
+    /// # Returns
+    ///
+    /// Paths of the empty subdirectories sorted by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or one of its subdirectories cannot be read.
+    pub fn empty_date_dirs(&self, format: &NameFormat) -> Result<Vec<PathBuf>, PhotoDaterError> {
+        let mut dirs = Vec::new();
+        for (path, _interval) in self.date_dirs(format)? {
+            if is_empty(&path)? {
+                dirs.push(path);
+            }
+        }
+        Ok(dirs)
+    }
+
+    /// Finds runs of date-named immediate subdirectories (see [`Directory::empty_date_dirs`])
+    /// that are at most `max_gap` days apart from each other, which could be merged
+    /// into a single directory.
+    ///
+    /// # Returns
+    ///
+    /// Runs of at least two subdirectories ordered by date, each with the merged
+    /// interval of all its subdirectories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be read.
+    pub fn suggest_merges(
+        &self,
+        max_gap: u32,
+        format: &NameFormat,
+    ) -> Result<Vec<(Vec<PathBuf>, FilesInterval)>, PhotoDaterError> {
+        let mut dirs = self.date_dirs(format)?;
+        dirs.sort_by_key(|(_path, interval)| interval.from);
+        let mut runs = Vec::<(Vec<PathBuf>, FilesInterval)>::new();
+        for (path, interval) in dirs {
+            match runs.last_mut() {
+                Some((paths, run))
+                    if (interval.from.date() - run.to.date()).num_days() <= max_gap.into() =>
+                {
+                    paths.push(path);
+                    *run = run.merge(&interval);
+                }
+                _ => runs.push((vec![path], interval)),
+            }
+        }
+        runs.retain(|(paths, _interval)| paths.len() > 1);
+        Ok(runs)
+    }
+
+    /// Reads each immediate subdirectory as a separate [`Directory`], e.g. to check
+    /// names of event folders independently of each other.
+    ///
+    /// Hidden subdirectories and symbolic links are skipped unless enabled by
+    /// [`ReadOptions::dot_files`] and [`ReadOptions::follow_symlinks`].
+    ///
+    /// # Returns
+    ///
+    /// Subdirectories sorted by path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or one of its subdirectories cannot be read.
+    pub fn subdirectories(&self, options: &ReadOptions) -> Result<Vec<Self>, PhotoDaterError> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.as_encoded_bytes().starts_with(b"."));
+            let is_dir = if options.follow_symlinks {
+                path.is_dir()
+            } else {
+                fs::symlink_metadata(&path)?.is_dir()
+            };
+            if is_dir && (options.dot_files || !hidden) {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        paths
+            .into_iter()
+            .map(|path| Self::try_from(path, options))
+            .collect()
+    }
+
+    /// Lists immediate subdirectories whose names start with a date, with the
+    /// interval parsed from the name, sorted by path.
+    fn date_dirs(
+        &self,
+        format: &NameFormat,
+    ) -> Result<Vec<(PathBuf, FilesInterval)>, PhotoDaterError> {
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(&self.directory)? {
+            let path = entry?.path();
+            let interval = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| FilesInterval::try_split(name, format));
+            if let Some((interval, _name)) = interval {
+                if fs::symlink_metadata(&path)?.is_dir() {
+                    dirs.push((path, interval));
+                }
+            }
+        }
+        dirs.sort_by(|(a, _), (b, _)| a.cmp(b));
+        Ok(dirs)
+    }
+}
+
+/// Returns `path` if it does not exist, otherwise the first free path with a
+/// number appended to the name, e.g. `2025-05-01 Trip (1)`.
+pub fn free_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    (1..)
+        .map(|n| {
+            let mut name = path.file_name().unwrap_or_default().to_os_string();
+            name.push(format!(" ({n})"));
+            path.with_file_name(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap()
+}
+
+/// Returns `true` if the directory contains nothing but [`IGNORABLE_FILES`].
+fn is_empty(path: &Path) -> io::Result<bool> {
+    fs::read_dir(path)?.try_fold(true, |empty, entry| {
+        let entry = entry?;
+        let ignorable = entry.file_type()?.is_file()
+            && IGNORABLE_FILES
+                .iter()
+                .any(|name| entry.file_name() == *name);
+        Ok(empty && ignorable)
+    })
+}
+
+/// Removes an empty directory found by [`Directory::empty_date_dirs`] together
+/// with the ignorable files inside it.
+///
+/// # Errors
+///
+/// Returns an error if the directory is no longer empty or cannot be removed.
+pub fn remove_empty_dir(path: &Path) -> io::Result<()> {
+    IGNORABLE_FILES
+        .iter()
+        .map(|name| path.join(name))
+        .filter(|file| file.is_file())
+        .try_for_each(fs::remove_file)?;
+    fs::remove_dir(path)
 }
 
 #[cfg(test)]
@@ -170,7 +460,7 @@ mod tests {
 
     use chrono::NaiveDateTime;
 
-    use crate::file::File;
+    use crate::file::{DateSource, File};
 
     use super::*;
 
@@ -180,10 +470,14 @@ mod tests {
             File {
                 path: PathBuf::new(),
                 created: NaiveDateTime::from_str("2025-05-01T12:00:00").unwrap(),
+                source: DateSource::Exif,
+                offset: None,
             },
             File {
                 path: PathBuf::new(),
                 created: NaiveDateTime::from_str("2025-05-03T12:00:00").unwrap(),
+                source: DateSource::Exif,
+                offset: None,
             },
         ]
     }
@@ -191,67 +485,76 @@ mod tests {
     #[test]
     fn name_status() {
         let [file1, file2] = test_files();
+        let format = NameFormat::default();
 
         // Single file
         let dir = Directory {
             directory: PathBuf::from("./2025-05-01 dir name"),
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::Valid);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::Valid);
 
         let dir = Directory {
             directory: PathBuf::from("./2025-05-02 dir name"),
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::Invalid);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::Invalid);
 
         let dir = Directory {
             directory: PathBuf::from("dir name"),
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::None);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::None);
 
         let dir = Directory {
             directory: PathBuf::from("./2025-05-01 dir name"),
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::Valid);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::Valid);
+
+        // Start time of the day in the name
+        let dir = Directory {
+            directory: PathBuf::from("./2025-05-01 14-30 dir name"),
+            files: Files::new([&file1].into_iter().cloned().collect()),
+        };
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::Valid);
 
         // Multiple files
         let dir = Directory {
             directory: PathBuf::from("./2025-05-01 - 03 dir name"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::Valid);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::Valid);
 
         let dir = Directory {
             directory: PathBuf::from("./2026-05-01 - 03 dir name"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::Invalid);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::Invalid);
 
         let dir = Directory {
             directory: PathBuf::from("./2025-05-01 - 04 dir name"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::SuperSet);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::SuperSet);
 
         let dir = Directory {
             directory: PathBuf::from("./2025-04-30 - 05-03 dir name"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::SuperSet);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::SuperSet);
 
         let dir = Directory {
             directory: PathBuf::from("./2025-04-30 - 2026-01-01 dir name"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
-        assert_eq!(dir.name_status().unwrap(), NameStatus::SuperSet);
+        assert_eq!(dir.name_status(&format).unwrap(), NameStatus::SuperSet);
     }
 
     #[test]
     fn rename() {
         let [file1, file2] = test_files();
+        let format = NameFormat::default();
 
         // Single file
         let dir = Directory {
@@ -259,7 +562,7 @@ mod tests {
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(0).unwrap(),
+            dir.rename(0, &format, None).unwrap(),
             (NameStatus::Valid, PathBuf::from("./2025-05-01 dir name"))
         );
 
@@ -268,11 +571,8 @@ mod tests {
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(0).unwrap(),
-            (
-                NameStatus::Invalid,
-                PathBuf::from("./2025-05-01 2025-05-03 dir name")
-            )
+            dir.rename(0, &format, None).unwrap(),
+            (NameStatus::Invalid, PathBuf::from("./2025-05-01 dir name"))
         );
 
         let dir = Directory {
@@ -280,7 +580,7 @@ mod tests {
             files: Files::new([&file1].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(0).unwrap(),
+            dir.rename(0, &format, None).unwrap(),
             (NameStatus::None, PathBuf::from("./2025-05-01 dir name"))
         );
 
@@ -289,14 +589,17 @@ mod tests {
             directory: PathBuf::from("./Too long interval"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
-        assert!(dir.rename(0).is_err());
+        assert!(matches!(
+            dir.rename(0, &format, None),
+            Err(PhotoDaterError::IntervalTooLarge { days: 2, .. })
+        ));
 
         let dir = Directory {
             directory: PathBuf::from("./2025-05-01 - 03 dir name"),
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(2).unwrap(),
+            dir.rename(2, &format, None).unwrap(),
             (
                 NameStatus::Valid,
                 PathBuf::from("./2025-05-01 - 03 dir name")
@@ -308,10 +611,22 @@ mod tests {
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(2).unwrap(),
+            dir.rename(2, &format, None).unwrap(),
             (
                 NameStatus::Invalid,
-                PathBuf::from("./2025-05-01 - 03 2026-05-01 - 03 dir name")
+                PathBuf::from("./2025-05-01 - 03 dir name")
+            )
+        );
+
+        let dir = Directory {
+            directory: PathBuf::from("./Trip 2025-05-02 Paris"),
+            files: Files::new([&file1, &file2].into_iter().cloned().collect()),
+        };
+        assert_eq!(
+            dir.rename(2, &format, None).unwrap(),
+            (
+                NameStatus::Invalid,
+                PathBuf::from("./2025-05-01 - 03 Trip Paris")
             )
         );
 
@@ -320,7 +635,7 @@ mod tests {
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(2).unwrap(),
+            dir.rename(2, &format, None).unwrap(),
             (
                 NameStatus::SuperSet,
                 PathBuf::from("./2025-05-01 - 04 dir name")
@@ -332,7 +647,7 @@ mod tests {
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(2).unwrap(),
+            dir.rename(2, &format, None).unwrap(),
             (
                 NameStatus::SuperSet,
                 PathBuf::from("./2025-04-30 - 05-03 dir name")
@@ -344,11 +659,256 @@ mod tests {
             files: Files::new([&file1, &file2].into_iter().cloned().collect()),
         };
         assert_eq!(
-            dir.rename(2).unwrap(),
+            dir.rename(2, &format, None).unwrap(),
             (
                 NameStatus::SuperSet,
                 PathBuf::from("./2025-04-30 - 2026-01-01 dir name")
             )
         );
     }
+
+    #[test]
+    fn rename_template() -> anyhow::Result<()> {
+        let [file1, file2] = test_files();
+        let format = NameFormat::default();
+        let rename = |name: &str, template: &str| -> anyhow::Result<_> {
+            Ok(Directory {
+                directory: PathBuf::from(name),
+                files: Files::new([&file1, &file2].into_iter().cloned().collect()),
+            }
+            .rename(2, &format, Some(&template.parse()?))?)
+        };
+
+        // Bracketed
+        assert_eq!(
+            rename("./Trip", "{name} [{interval}]")?,
+            (NameStatus::None, PathBuf::from("./Trip [2025-05-01 - 03]"))
+        );
+        assert_eq!(
+            rename("./Trip [2025-05-01 - 03]", "{name} [{interval}]")?,
+            (NameStatus::Valid, PathBuf::from("./Trip [2025-05-01 - 03]"))
+        );
+        assert_eq!(
+            rename("./Trip [2026-05-01]", "{name} [{interval}]")?,
+            (
+                NameStatus::Invalid,
+                PathBuf::from("./Trip [2025-05-01 - 03]")
+            )
+        );
+        assert_eq!(
+            rename("./2025-05-01 Trip", "{name} [{interval}]")?,
+            (NameStatus::None, PathBuf::from("./Trip [2025-05-01 - 03]"))
+        );
+
+        // Suffixed
+        assert_eq!(
+            rename("./Trip 2026-05-01", "{name} {interval}")?,
+            (NameStatus::Invalid, PathBuf::from("./Trip 2025-05-01 - 03"))
+        );
+        assert_eq!(
+            rename("./Trip 2025-05-01 - 03", "{name} {interval}")?,
+            (NameStatus::Valid, PathBuf::from("./Trip 2025-05-01 - 03"))
+        );
+
+        // Default front date layout
+        assert_eq!(
+            rename("./Trip", "{interval} {name}")?,
+            (NameStatus::None, PathBuf::from("./2025-05-01 - 03 Trip"))
+        );
+        assert_eq!(
+            rename("./2026-05-01 Trip", "{interval} {name}")?,
+            (NameStatus::Invalid, PathBuf::from("./2025-05-01 - 03 Trip"))
+        );
+        assert_eq!(
+            rename("./2025-05-01 - 03", "{interval} {name}")?,
+            (NameStatus::Valid, PathBuf::from("./2025-05-01 - 03"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rename_interval() {
+        let [file1, file2] = test_files();
+        let format = NameFormat::default();
+        let rename_interval = |name: &str, max_interval| {
+            Directory {
+                directory: PathBuf::from(name),
+                files: Files::new([&file1, &file2].into_iter().cloned().collect()),
+            }
+            .rename_interval(max_interval, &format)
+        };
+
+        assert_eq!(
+            rename_interval("./2025-05-01 Trip", 2).unwrap(),
+            PathBuf::from("./2025-05-01 - 03 Trip")
+        );
+        assert_eq!(
+            rename_interval("./2025-04-30 - 05-04 Trip", 2).unwrap(),
+            PathBuf::from("./2025-05-01 - 03 Trip")
+        );
+        assert_eq!(
+            rename_interval("./2025-05-01 - 03 Trip", 2).unwrap(),
+            PathBuf::from("./2025-05-01 - 03 Trip")
+        );
+        assert_eq!(
+            rename_interval("./2025-05-01", 2).unwrap(),
+            PathBuf::from("./2025-05-01 - 03")
+        );
+        assert_eq!(
+            rename_interval("./Trip", 2).unwrap(),
+            PathBuf::from("./2025-05-01 - 03 Trip")
+        );
+        // Names already matching the files keep the date where it is
+        assert_eq!(
+            rename_interval("./Trip 2025-05-01 - 03 Paris", 2).unwrap(),
+            PathBuf::from("./Trip 2025-05-01 - 03 Paris")
+        );
+        // The date is found in the middle of the name too
+        assert_eq!(
+            rename_interval("./Trip 2025-05-02 Paris", 2).unwrap(),
+            PathBuf::from("./2025-05-01 - 03 Trip Paris")
+        );
+        assert!(matches!(
+            rename_interval("./2025-05-01 Trip", 1),
+            Err(PhotoDaterError::IntervalTooLarge { days: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn rename_target_exists() -> anyhow::Result<()> {
+        let [file1, _] = test_files();
+        let root = tempfile::tempdir()?;
+        fs::create_dir(root.path().join("Trip"))?;
+        fs::create_dir(root.path().join("2025-05-01 Trip"))?;
+        let dir = Directory {
+            directory: root.path().join("Trip"),
+            files: Files::new(vec![file1]),
+        };
+
+        let (status, new_path) = dir.rename(0, &NameFormat::default(), None)?;
+        assert_eq!(status, NameStatus::None);
+        assert_eq!(new_path, root.path().join("2025-05-01 Trip"));
+        assert!(new_path.exists());
+        assert_eq!(
+            free_path(&new_path),
+            root.path().join("2025-05-01 Trip (1)")
+        );
+
+        fs::create_dir(root.path().join("2025-05-01 Trip (1)"))?;
+        assert_eq!(
+            free_path(&new_path),
+            root.path().join("2025-05-01 Trip (2)")
+        );
+        assert_eq!(
+            free_path(&dir.directory.join("free")),
+            dir.directory.join("free")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn subdirectories() -> anyhow::Result<()> {
+        use crate::test_utils::write_photo;
+
+        let root = tempfile::tempdir()?;
+        let path = |name: &str| root.path().join(name);
+        write_photo(path("2025-05-01 Trip/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("2025-05-01 Trip/day 2/2.jpg"), "2025:05:02 12:13:14");
+        write_photo(path("Party/3.jpg"), "2025:06:01 12:13:14");
+        write_photo(path(".thumbnails/4.jpg"), "2025:06:01 12:13:14");
+        write_photo(path("5.jpg"), "2025:06:01 12:13:14");
+
+        let dir = Directory::try_from(root.path().to_path_buf(), &ReadOptions::default())?;
+        let status = |options| -> anyhow::Result<Vec<(PathBuf, NameStatus)>> {
+            dir.subdirectories(&options)?
+                .into_iter()
+                .map(|sub| {
+                    Ok((
+                        sub.directory.clone(),
+                        sub.name_status(&NameFormat::default())?,
+                    ))
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            status(ReadOptions::default())?,
+            vec![
+                (path("2025-05-01 Trip"), NameStatus::Invalid),
+                (path("Party"), NameStatus::None),
+            ]
+        );
+        let options = ReadOptions {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(
+            status(options)?,
+            vec![
+                (path("2025-05-01 Trip"), NameStatus::Valid),
+                (path("Party"), NameStatus::None),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn empty_date_dirs() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        [
+            "2025-05-01",
+            "2025-05-02",
+            "2025-05-03 - 04 Trip",
+            "2025-05-05",
+            "Empty",
+        ]
+        .iter()
+        .try_for_each(|name| fs::create_dir(path(name)))?;
+        fs::write(path("2025-05-02/photo.jpg"), "")?;
+        fs::write(path("2025-05-03 - 04 Trip/.DS_Store"), "")?;
+        fs::create_dir(path("2025-05-05/sub"))?;
+        let directory = Directory::try_from(dir.path().to_path_buf(), &ReadOptions::default())?;
+
+        let empty = directory.empty_date_dirs(&NameFormat::default())?;
+        assert_eq!(empty, [path("2025-05-01"), path("2025-05-03 - 04 Trip")]);
+
+        empty.iter().try_for_each(|dir| remove_empty_dir(dir))?;
+        assert!(!path("2025-05-01").exists());
+        assert!(!path("2025-05-03 - 04 Trip").exists());
+        assert!(path("2025-05-02/photo.jpg").exists());
+        assert!(path("Empty").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn suggest_merges() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        [
+            "2025-05-01",
+            "2025-05-02 - 04 Trip",
+            "2025-05-06",
+            "2025-05-20",
+        ]
+        .iter()
+        .try_for_each(|name| fs::create_dir(path(name)))?;
+        let directory = Directory::try_from(dir.path().to_path_buf(), &ReadOptions::default())?;
+        let format = NameFormat::default();
+
+        let merges = directory.suggest_merges(1, &format)?;
+        assert_eq!(merges.len(), 1);
+        assert_eq!(
+            merges[0].0,
+            [path("2025-05-01"), path("2025-05-02 - 04 Trip")]
+        );
+        assert_eq!(merges[0].1.format(&format), "2025-05-01 - 04");
+
+        let merges = directory.suggest_merges(2, &format)?;
+        assert_eq!(merges.len(), 1);
+        assert_eq!(merges[0].1.format(&format), "2025-05-01 - 06");
+
+        assert!(directory.suggest_merges(0, &format)?.is_empty());
+        Ok(())
+    }
 }
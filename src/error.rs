@@ -0,0 +1,80 @@
+use chrono::NaiveDateTime;
+use std::{io, path::PathBuf};
+use thiserror::Error;
+
+/// Failures of reading and renaming photo directories.
+///
+/// Errors returned by [`crate::run`] can be matched on after downcasting,
+/// e.g. `error.downcast_ref::<PhotoDaterError>()`.
+#[derive(Debug, Error)]
+pub enum PhotoDaterError {
+    /// The processed path is not a directory
+    #[error("{0:?} is not directory")]
+    NotADirectory(PathBuf),
+    /// Files of the directory span more days than allowed
+    #[error(
+        "Interval from {from} to {to} is too large ({days} days, {} over the maximum of {max})",
+        .days - i64::from(*.max)
+    )]
+    IntervalTooLarge {
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        days: i64,
+        max: u32,
+    },
+    /// Files of the directory span more hours than allowed
+    #[error(
+        "Interval from {from} to {to} is too large ({hours} hours, {} over the maximum of {max})",
+        .hours - i64::from(*.max)
+    )]
+    IntervalTooLargeHours {
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        hours: i64,
+        max: u32,
+    },
+    /// Files of the directory span fewer days than required, e.g. because
+    /// directories of separate events were merged
+    #[error(
+        "Interval from {from} to {to} is too small ({days} days, {} under the minimum of {min})",
+        i64::from(*.min) - .days
+    )]
+    IntervalTooSmall {
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        days: i64,
+        min: u32,
+    },
+    /// Files of the directory span fewer hours than required
+    #[error(
+        "Interval from {from} to {to} is too small ({hours} hours, {} under the minimum of {min})",
+        i64::from(*.min) - .hours
+    )]
+    IntervalTooSmallHours {
+        from: NaiveDateTime,
+        to: NaiveDateTime,
+        hours: i64,
+        min: u32,
+    },
+    /// The directory contains no files with a creation date
+    #[error("There are no files with dates")]
+    NoFilesWithDates,
+    /// The directory contains files with dates, but none within `--since` and `--until`
+    #[error("There are no files in range given by --since and --until")]
+    NoFilesInRange,
+    /// The new name of a renamed directory is taken by another directory or file
+    #[error("Cannot rename {from:?}, {to:?} already exists")]
+    TargetExists { from: PathBuf, to: PathBuf },
+    /// A path has no file or directory name, e.g. `..`
+    #[error("Cannot get file name from {0:?}")]
+    NoFileName(PathBuf),
+    /// A file or directory name is not valid UTF-8
+    #[error("File name {0:?} is not UTF-8 valid string")]
+    NonUtf8Name(PathBuf),
+    /// A date stored in the file cannot be parsed
+    #[error("Failed to parse date {date:?} of {path:?}")]
+    DateParse { path: PathBuf, date: String },
+    /// Reading the filesystem failed
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
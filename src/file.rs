@@ -1,72 +1,271 @@
-use anyhow::{Context, Result};
-use chrono::NaiveDateTime;
+#[cfg(feature = "pdf")]
+use crate::pdf;
+use crate::{error::PhotoDaterError, filename_date, files::ReadOptions, xmp};
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Offset, TimeZone, Timelike};
+use chrono_tz::Tz;
+use serde::Serialize;
 use std::{
     cmp::Ordering,
-    io::{Read, Seek},
+    fmt::Display,
+    hash::{DefaultHasher, Hash, Hasher},
+    io::{self, Read, Seek},
     ops::{Deref, DerefMut},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
+/// EXIF tags holding the creation date, in order of preference, each followed by
+/// the tags holding its fractions of a second and its offset from UTC.
+const DATE_TAGS: [(exif::Tag, exif::Tag, exif::Tag); 3] = [
+    (
+        exif::Tag::DateTimeOriginal,
+        exif::Tag::SubSecTimeOriginal,
+        exif::Tag::OffsetTimeOriginal,
+    ),
+    (
+        exif::Tag::DateTimeDigitized,
+        exif::Tag::SubSecTimeDigitized,
+        exif::Tag::OffsetTimeDigitized,
+    ),
+    (
+        exif::Tag::DateTime,
+        exif::Tag::SubSecTime,
+        exif::Tag::OffsetTime,
+    ),
+];
+
 /// Represents a photo file with its filesystem path and creation date.
 ///
 /// This struct encapsulates a file's location and the creation timestamp
 /// extracted from its EXIF metadata. Only files with valid EXIF creation
 /// dates.
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// When serialized, the path is written as a UTF-8 string and serialization
+/// fails for paths that are not valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct File {
     pub path: PathBuf,
     pub created: NaiveDateTime,
+    #[serde(skip)]
+    pub source: DateSource,
+    /// Offset of `created` from UTC, if known
+    #[serde(skip)]
+    pub offset: Option<FixedOffset>,
+}
+
+/// Where the creation date of a file was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateSource {
+    /// EXIF metadata of the file
+    Exif,
+    /// XMP metadata of a WebP or GIF file, see [`xmp::read`]
+    Xmp,
+    /// Messaging app file name, see [`filename_date::parse`]
+    FileName,
+    /// Document information of a PDF file, see [`pdf::read`]
+    #[cfg(feature = "pdf")]
+    Pdf,
+}
+
+impl Display for DateSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateSource::Exif => write!(f, "exif"),
+            DateSource::Xmp => write!(f, "xmp"),
+            DateSource::FileName => write!(f, "filename"),
+            #[cfg(feature = "pdf")]
+            DateSource::Pdf => write!(f, "pdf"),
+        }
+    }
+}
+
+/// Reads a GPS coordinate in degrees from the EXIF `tag` holding degrees, minutes and
+/// seconds, negated if the `reference` tag (e.g. `GPSLatitudeRef`) equals `negative`.
+fn gps_coordinate(
+    exif: &exif::Exif,
+    tag: exif::Tag,
+    reference: exif::Tag,
+    negative: u8,
+) -> Option<f64> {
+    let exif::Value::Rational(dms) = &exif.get_field(tag, exif::In::PRIMARY)?.value else {
+        return None;
+    };
+    let degrees = dms
+        .iter()
+        .zip([1.0, 60.0, 3600.0])
+        .map(|(value, unit)| value.to_f64() / unit)
+        .sum::<f64>();
+    let negative = match exif
+        .get_field(reference, exif::In::PRIMARY)
+        .map(|f| &f.value)
+    {
+        Some(exif::Value::Ascii(values)) => {
+            values.first().and_then(|v| v.first()) == Some(&negative)
+        }
+        _ => false,
+    };
+    let degrees = if negative { -degrees } else { degrees };
+    (!dms.is_empty() && degrees.is_finite()).then_some(degrees)
+}
+
+/// Parses the EXIF fractions of a second (e.g. `52` for 0.52 s) into nanoseconds.
+fn parse_subsec(subsec: &str) -> Option<u32> {
+    if subsec.is_empty() || !subsec.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    format!("{:0<9}", &subsec[..subsec.len().min(9)])
+        .parse()
+        .ok()
 }
 
 impl File {
+    /// Returns the creation time in UTC if its offset is known, otherwise the
+    /// creation time as recorded.
+    pub fn instant(&self) -> NaiveDateTime {
+        self.offset
+            .map_or(self.created, |offset| self.created - offset)
+    }
+
     /// This method attempts to parse EXIF metadata from the provided reader
-    /// and extract the DateTimeOriginal field.
+    /// and extract the creation date.
+    ///
+    /// The date is taken from the first of `DateTimeOriginal`, `DateTimeDigitized`
+    /// and `DateTime` tags that is present and parses successfully. Fractions of
+    /// a second from the matching `SubSecTime*` tag are added to the date and the
+    /// offset from the matching `OffsetTime*` tag is returned alongside it.
     ///
     /// # Arguments
     ///
     /// * `reader` - A reader that implements `Read + Seek` for accessing file data
+    /// * `path` - Path of the read file, reported in errors
     ///
     /// # Returns
     ///
-    /// Returns `Ok(Some(NaiveDateTime))` if EXIF data is found and contains a valid
-    /// creation date, `Ok(None)` if no EXIF data or creation date is found, or an
-    /// error if the date string cannot be parsed.
+    /// Returns `Ok(Some((NaiveDateTime, Option<FixedOffset>)))` if EXIF data is found
+    /// and contains a valid creation date, `Ok(None)` if no EXIF data or creation date
+    /// is found, or an error if date tags are present but none of them can be parsed.
     ///
     /// # Supported Date Formats
     ///
     /// - `%Y-%m-%d %H:%M:%S` (e.g., "2025-05-01 14:30:25")
     /// - `%Y:%m:%d %H:%M:%S` (e.g., "2025:05:01 14:30:25")
-    fn read_time<R>(reader: R) -> Result<Option<NaiveDateTime>>
+    fn read_time<R>(
+        reader: R,
+        path: &Path,
+    ) -> Result<Option<(NaiveDateTime, Option<FixedOffset>)>, PhotoDaterError>
     where
         R: Read + Seek,
     {
         let mut bufreader = std::io::BufReader::new(reader);
-        exif::Reader::new()
-            .read_from_container(&mut bufreader)
-            .ok()
-            .and_then(|exif| {
-                exif.fields()
-                    .find(|f| f.tag == exif::Tag::DateTimeOriginal)
-                    .map(|f| {
-                        let date_str = f.display_value().with_unit(&exif).to_string();
-                        let created = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S")
-                            .or_else(|_| {
-                                NaiveDateTime::parse_from_str(&date_str, "%Y:%m:%d %H:%M:%S")
-                            })
-                            .context(format!("Failed to parse date: {date_str}"))?;
-                        Ok(created)
-                    })
+        let Ok(exif) = exif::Reader::new().read_from_container(&mut bufreader) else {
+            return Ok(None);
+        };
+        let field = |tag: exif::Tag| exif.fields().find(|f| f.tag == tag);
+        let ascii = |tag: exif::Tag| match &field(tag)?.value {
+            exif::Value::Ascii(values) => std::str::from_utf8(values.first()?).ok().map(str::trim),
+            _ => None,
+        };
+        let dates = DATE_TAGS
+            .iter()
+            .filter_map(|(tag, subsec, offset)| Some((field(*tag)?, *subsec, *offset)))
+            .map(|(f, subsec, offset)| {
+                let date_str = f.display_value().with_unit(&exif).to_string();
+                let created = NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S")
+                    .or_else(|_| NaiveDateTime::parse_from_str(&date_str, "%Y:%m:%d %H:%M:%S"))
+                    .map_err(|_| PhotoDaterError::DateParse {
+                        path: path.to_path_buf(),
+                        date: date_str,
+                    })?;
+                let created = ascii(subsec)
+                    .and_then(parse_subsec)
+                    .and_then(|nanos| created.with_nanosecond(nanos))
+                    .unwrap_or(created);
+                let offset = ascii(offset).and_then(|offset| offset.parse().ok());
+                Ok((created, offset))
             })
-            .transpose()
+            .collect::<Vec<Result<_, _>>>();
+        match dates.iter().find_map(|date| date.as_ref().ok()) {
+            Some(created) => Ok(Some(*created)),
+            None => dates.into_iter().next().transpose(),
+        }
+    }
+
+    /// Returns the last modification time of the file from the filesystem in the local time zone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file metadata cannot be read or the platform does not
+    /// provide the modification time.
+    pub fn modified(&self) -> io::Result<NaiveDateTime> {
+        let modified = std::fs::metadata(&self.path)?.modified()?;
+        Ok(DateTime::<Local>::from(modified).naive_local())
+    }
+
+    /// Reads the GPS position of the file from its EXIF metadata.
+    ///
+    /// Returns the latitude and longitude in degrees, negative for the southern and
+    /// western hemispheres, or `None` if the file has no EXIF GPS position.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened.
+    pub fn location(&self) -> io::Result<Option<(f64, f64)>> {
+        let mut reader = io::BufReader::new(std::fs::File::open(&self.path)?);
+        let Ok(exif) = exif::Reader::new().read_from_container(&mut reader) else {
+            return Ok(None);
+        };
+        let latitude = gps_coordinate(
+            &exif,
+            exif::Tag::GPSLatitude,
+            exif::Tag::GPSLatitudeRef,
+            b'S',
+        );
+        let longitude = gps_coordinate(
+            &exif,
+            exif::Tag::GPSLongitude,
+            exif::Tag::GPSLongitudeRef,
+            b'W',
+        );
+        Ok(latitude.zip(longitude))
+    }
+
+    /// Returns the size of the file and a hash of its content.
+    ///
+    /// Files with identical content have equal hashes, files with equal hashes
+    /// still have to be compared to confirm they are identical.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read.
+    pub fn hash(&self) -> io::Result<(u64, u64)> {
+        let content = std::fs::read(&self.path)?;
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        Ok((content.len() as u64, hasher.finish()))
+    }
+
+    /// Returns `true` if the path has one of the given extensions, ignoring case.
+    pub fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| extensions.iter().any(|e| e.eq_ignore_ascii_case(extension)))
     }
 
     /// This method opens the file at the specified path and attempts to extract
-    /// the creation date from its EXIF metadata. Files without EXIF data or
-    /// without a DateTimeOriginal field are skipped (return None).
+    /// the creation date from its EXIF metadata. WebP and GIF files without EXIF
+    /// date are also searched for XMP metadata, and with the `pdf` feature PDF files
+    /// for the creation date of the document. If enabled by
+    /// [`ReadOptions::name_dates`], files without EXIF date fall back to the date
+    /// in a messaging app file name. Other files are skipped (return None).
+    ///
+    /// With [`ReadOptions::timezone`], dates without an offset get the offset of that
+    /// zone. With [`ReadOptions::utc_to`], dates with a known offset from UTC are
+    /// converted using that offset, other dates are assumed to be in UTC.
     ///
     /// # Arguments
     ///
     /// * `path` - Path to the file to read
+    /// * `options` - Options adjusting the read date, e.g. time zone conversion
     ///
     /// # Returns
     ///
@@ -80,14 +279,57 @@ impl File {
     /// - The file cannot be opened (permissions, not found, etc.)
     /// - The EXIF date string is present but cannot be parsed
     /// - I/O errors occur while reading the file
-    pub fn read(path: PathBuf) -> Result<Option<Self>> {
+    pub fn read(path: PathBuf, options: &ReadOptions) -> Result<Option<Self>, PhotoDaterError> {
         let file = std::fs::File::open(&path)?;
-        Self::read_time(file)
-            .context(format!("Path: {path:?}"))
-            .map(|opt_time| opt_time.map(|created| File { path, created }))
+        let created = match Self::read_time(file, &path)? {
+            Some((created, offset)) => Some((created, offset, DateSource::Exif)),
+            None if Self::has_extension(&path, &xmp::EXTENSIONS) => {
+                xmp::read(&std::fs::read(&path)?).map(|created| (created, None, DateSource::Xmp))
+            }
+            #[cfg(feature = "pdf")]
+            None if Self::has_extension(&path, &[pdf::EXTENSION]) => {
+                pdf::read(&std::fs::read(&path)?).map(|created| (created, None, DateSource::Pdf))
+            }
+            None => None,
+        }
+        .map(|(created, offset, source)| {
+            let offset = offset.or_else(|| options.timezone.map(|tz| local_offset(tz, &created)));
+            (created, offset, source)
+        })
+        .map(|(created, offset, source)| match options.utc_to {
+            Some(tz) => {
+                let utc = offset.map_or(created, |offset| created - offset);
+                let local = tz.from_utc_datetime(&utc);
+                (local.naive_local(), Some(local.offset().fix()), source)
+            }
+            None => (created, offset, source),
+        });
+        let created = match created {
+            None if options.name_dates => path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(filename_date::parse)
+                .map(|created| (created, None, DateSource::FileName)),
+            created => created,
+        };
+        Ok(created.map(|(created, offset, source)| File {
+            path,
+            created,
+            source,
+            offset,
+        }))
     }
 }
 
+/// Returns the offset of `tz` at the local time `created`. Times skipped by a daylight
+/// saving change take the offset the zone has at the same time in UTC.
+fn local_offset(tz: Tz, created: &NaiveDateTime) -> FixedOffset {
+    tz.offset_from_local_datetime(created)
+        .earliest()
+        .unwrap_or_else(|| tz.offset_from_utc_datetime(created))
+        .fix()
+}
+
 /// Wrapper type that adds path-based ordering to any type that dereferences to File.
 ///
 /// This struct allows sorting collections of files (or file references) by their
@@ -139,10 +381,71 @@ impl<T> Deref for ByPath<T> {
     }
 }
 
+/// Wrapper type that adds file name-based ordering to any type that dereferences to File.
+///
+/// Unlike [`ByPath`], directories of the files are ignored, so files read from several
+/// subdirectories are interleaved by their names (e.g. `b/1.jpg` sorts before `a/2.jpg`).
+/// Files with the same name are ordered by path, so the ordering is total.
+pub struct ByFileName<T>(pub T);
+
+impl<T> Ord for ByFileName<T>
+where
+    T: Deref<Target = File>,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .path
+            .file_name()
+            .cmp(&other.0.path.file_name())
+            .then_with(|| self.0.path.cmp(&other.0.path))
+    }
+}
+impl<T> PartialOrd for ByFileName<T>
+where
+    T: Deref<Target = File>,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<T> PartialEq for ByFileName<T>
+where
+    T: Deref<Target = File>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0.path == other.0.path
+    }
+}
+impl<T> Eq for ByFileName<T> where T: Deref<Target = File> {}
+
+impl<'a, T> From<&'a T> for ByFileName<&'a T> {
+    fn from(value: &'a T) -> Self {
+        ByFileName::<&'a T>(value)
+    }
+}
+
+impl<T> DerefMut for ByFileName<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+impl<T> Deref for ByFileName<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
 /// Wrapper type that adds creation date-based ordering to any type that dereferences to File.
 ///
 /// This struct allows sorting collections of files (or file references) by their
-/// creation timestamps in chronological order.
+/// creation timestamps in chronological order. Files with known offset from UTC are
+/// compared by their UTC time (see [`File::instant`]), so photos from different time
+/// zones keep their real order. Fractions of a second order photos of a burst.
+///
+/// Files created at the same instant are ordered by path, so the ordering is total
+/// and files keep the same order across runs.
 pub struct ByCreatedDate<T>(pub T);
 
 impl<T> Ord for ByCreatedDate<T>
@@ -150,7 +453,10 @@ where
     T: Deref<Target = File>,
 {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.0.created.cmp(&other.0.created)
+        self.0
+            .instant()
+            .cmp(&other.0.instant())
+            .then_with(|| self.0.path.cmp(&other.0.path))
     }
 }
 impl<T> PartialOrd for ByCreatedDate<T>
@@ -166,7 +472,7 @@ where
     T: Deref<Target = File>,
 {
     fn eq(&self, other: &Self) -> bool {
-        self.0.created == other.0.created
+        self.0.instant() == other.0.instant() && self.0.path == other.0.path
     }
 }
 impl<T> Eq for ByCreatedDate<T> where T: Deref<Target = File> {}
@@ -204,16 +510,345 @@ mod tests {
         )
     }
 
+    #[test]
+    fn read_time_fallback_tags() -> Result<()> {
+        use crate::test_utils::exif_jpeg;
+        use exif::Tag;
+        use std::io::Cursor;
+
+        let read = |fields: &[(Tag, &str)]| {
+            File::read_time(Cursor::new(exif_jpeg(fields)), Path::new("photo.jpg"))
+                .map(|time| time.map(|(created, _offset)| created))
+        };
+
+        assert_eq!(read(&[(Tag::Software, "photo-dater")])?, None);
+        assert_eq!(
+            read(&[(Tag::DateTime, "2025:05:03 10:00:00")])?,
+            Some(datetime(2025, 5, 3, 10, 0, 0))
+        );
+        assert_eq!(
+            read(&[
+                (Tag::DateTime, "2025:05:03 10:00:00"),
+                (Tag::DateTimeDigitized, "2025:05:02 10:00:00"),
+            ])?,
+            Some(datetime(2025, 5, 2, 10, 0, 0))
+        );
+        assert_eq!(
+            read(&[
+                (Tag::DateTime, "2025:05:03 10:00:00"),
+                (Tag::DateTimeDigitized, "2025:05:02 10:00:00"),
+                (Tag::DateTimeOriginal, "2025:05:01 10:00:00"),
+            ])?,
+            Some(datetime(2025, 5, 1, 10, 0, 0))
+        );
+
+        // Unparseable tags are skipped in favour of the next one
+        assert_eq!(
+            read(&[
+                (Tag::DateTimeOriginal, "not a date"),
+                (Tag::DateTime, "2025:05:03 10:00:00"),
+            ])?,
+            Some(datetime(2025, 5, 3, 10, 0, 0))
+        );
+        assert!(matches!(
+            read(&[(Tag::DateTimeOriginal, "not a date")]),
+            Err(PhotoDaterError::DateParse { date, .. }) if date.contains("not a date")
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn read_time_subsec_and_offset() -> Result<()> {
+        use crate::test_utils::exif_jpeg;
+        use exif::Tag;
+        use std::io::Cursor;
+
+        let read = |fields: &[(Tag, &str)]| {
+            File::read_time(Cursor::new(exif_jpeg(fields)), Path::new("photo.jpg"))
+        };
+        let created = datetime(2025, 5, 1, 10, 0, 0);
+
+        assert_eq!(
+            read(&[(Tag::DateTimeOriginal, "2025:05:01 10:00:00")])?,
+            Some((created, None))
+        );
+        assert_eq!(
+            read(&[
+                (Tag::DateTimeOriginal, "2025:05:01 10:00:00"),
+                (Tag::SubSecTimeOriginal, "52"),
+                (Tag::OffsetTimeOriginal, "+02:00"),
+            ])?,
+            Some((
+                created.with_nanosecond(520_000_000).unwrap(),
+                FixedOffset::east_opt(2 * 3600)
+            ))
+        );
+
+        // Fractions and offsets belong to the tag they are read with
+        assert_eq!(
+            read(&[
+                (Tag::DateTime, "2025:05:01 10:00:00"),
+                (Tag::SubSecTimeOriginal, "52"),
+                (Tag::OffsetTimeOriginal, "+02:00"),
+                (Tag::OffsetTime, "-05:00"),
+            ])?,
+            Some((created, FixedOffset::west_opt(5 * 3600)))
+        );
+
+        // Invalid values are ignored
+        assert_eq!(
+            read(&[
+                (Tag::DateTimeOriginal, "2025:05:01 10:00:00"),
+                (Tag::SubSecTimeOriginal, "5x"),
+                (Tag::OffsetTimeOriginal, "   :  "),
+            ])?,
+            Some((created, None))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_offset_to_timezone() -> Result<()> {
+        use crate::test_utils::exif_jpeg;
+        use exif::Tag;
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("photo.jpg");
+        std::fs::write(
+            &path,
+            exif_jpeg(&[
+                (Tag::DateTimeOriginal, "2025:05:01 23:30:00"),
+                (Tag::OffsetTimeOriginal, "-04:00"),
+            ]),
+        )?;
+
+        // Local time of the camera is kept, the offset is stored alongside it
+        let file = File::read(path.clone(), &ReadOptions::default())?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 23, 30, 0));
+        assert_eq!(file.instant(), datetime(2025, 5, 2, 3, 30, 0));
+
+        // Known offset is used instead of assuming UTC
+        let options = ReadOptions {
+            utc_to: Some(chrono_tz::Europe::Prague),
+            ..Default::default()
+        };
+        let file = File::read(path, &options)?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 2, 5, 30, 0));
+        assert_eq!(file.offset, FixedOffset::east_opt(2 * 3600));
+        Ok(())
+    }
+
+    #[test]
+    fn read_utc_to_timezone() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("photo.jpg");
+        crate::test_utils::write_photo(&path, "2025:05:01 23:30:00");
+
+        let file = File::read(path.clone(), &ReadOptions::default())?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 23, 30, 0));
+
+        let options = ReadOptions {
+            utc_to: Some(chrono_tz::Europe::Prague),
+            ..Default::default()
+        };
+        let file = File::read(path.clone(), &options)?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 2, 1, 30, 0));
+
+        let options = ReadOptions {
+            utc_to: Some(chrono_tz::America::New_York),
+            ..Default::default()
+        };
+        let file = File::read(path, &options)?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 19, 30, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn read_camera_timezone() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("photo.jpg");
+        crate::test_utils::write_photo(&path, "2025:05:01 23:30:00");
+
+        // The local time is kept, the offset of the zone is stored alongside it
+        let options = ReadOptions {
+            timezone: Some(chrono_tz::America::New_York),
+            ..Default::default()
+        };
+        let file = File::read(path.clone(), &options)?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 23, 30, 0));
+        assert_eq!(file.offset, FixedOffset::west_opt(4 * 3600));
+
+        let read_in_prague = |timezone| -> Result<NaiveDateTime> {
+            let options = ReadOptions {
+                timezone: Some(timezone),
+                utc_to: Some(chrono_tz::Europe::Prague),
+                ..Default::default()
+            };
+            Ok(File::read(path.clone(), &options)?.unwrap().created)
+        };
+        assert_eq!(
+            read_in_prague(chrono_tz::America::New_York)?,
+            datetime(2025, 5, 2, 5, 30, 0)
+        );
+        assert_eq!(
+            read_in_prague(chrono_tz::Asia::Tokyo)?,
+            datetime(2025, 5, 1, 16, 30, 0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn location() -> Result<()> {
+        use crate::test_utils::{write_photo, write_photo_at};
+
+        let dir = tempfile::tempdir()?;
+        let read = |name: &str| -> Result<Option<(f64, f64)>> {
+            let file = File::read(dir.path().join(name), &ReadOptions::default())?.unwrap();
+            Ok(file.location()?)
+        };
+        write_photo_at(
+            dir.path().join("paris.jpg"),
+            "2025:05:01 12:00:00",
+            48.8566,
+            2.3522,
+        );
+        write_photo_at(
+            dir.path().join("rio.jpg"),
+            "2025:05:01 12:00:00",
+            -22.9068,
+            -43.1729,
+        );
+        write_photo(dir.path().join("none.jpg"), "2025:05:01 12:00:00");
+
+        let (latitude, longitude) = read("paris.jpg")?.unwrap();
+        assert!((latitude - 48.8566).abs() < 1e-6 && (longitude - 2.3522).abs() < 1e-6);
+        let (latitude, longitude) = read("rio.jpg")?.unwrap();
+        assert!((latitude + 22.9068).abs() < 1e-6 && (longitude + 43.1729).abs() < 1e-6);
+        assert_eq!(read("none.jpg")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn read_name_dates() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let shared = dir.path().join("IMG-20250501-WA0001.jpg");
+        std::fs::write(&shared, "no exif here")?;
+        let photo = dir.path().join("IMG-20250502-WA0002.jpg");
+        crate::test_utils::write_photo(&photo, "2025:05:01 23:30:00");
+
+        assert_eq!(File::read(shared.clone(), &ReadOptions::default())?, None);
+
+        let options = ReadOptions {
+            name_dates: true,
+            ..Default::default()
+        };
+        let file = File::read(shared, &options)?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 0, 0, 0));
+        let file = File::read(photo, &options)?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 23, 30, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn read_heic() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("IMG_0001.HEIC");
+        std::fs::write(
+            &path,
+            crate::test_utils::exif_heic(&[(exif::Tag::DateTimeOriginal, "2025:05:01 12:13:14")]),
+        )?;
+
+        let file = File::read(path, &ReadOptions::default())?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 12, 13, 14));
+        Ok(())
+    }
+
+    #[test]
+    fn read_avif() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("photo.avif");
+        std::fs::write(
+            &path,
+            crate::test_utils::exif_avif(&[(exif::Tag::DateTimeOriginal, "2025:05:01 12:13:14")]),
+        )?;
+
+        let file = File::read(path, &ReadOptions::default())?.unwrap();
+        assert_eq!(file.created, datetime(2025, 5, 1, 12, 13, 14));
+        Ok(())
+    }
+
+    #[test]
+    fn read_webp_and_gif() -> Result<()> {
+        use crate::test_utils::{exif_webp, xmp_gif, xmp_webp};
+
+        let dir = tempfile::tempdir()?;
+        let xmp =
+            r#"<x:xmpmeta><rdf:Description xmp:CreateDate="2025-05-02T10:00:00"/></x:xmpmeta>"#;
+        let read = |name: &str, data: Vec<u8>| -> Result<Option<File>, PhotoDaterError> {
+            let path = dir.path().join(name);
+            std::fs::write(&path, data)?;
+            File::read(path, &ReadOptions::default())
+        };
+
+        let file = read(
+            "exif.webp",
+            exif_webp(&[(exif::Tag::DateTimeOriginal, "2025:05:01 12:13:14")]),
+        )?
+        .unwrap();
+        assert_eq!(
+            (file.created, file.source),
+            (datetime(2025, 5, 1, 12, 13, 14), DateSource::Exif)
+        );
+
+        let file = read("xmp.webp", xmp_webp(xmp))?.unwrap();
+        assert_eq!(
+            (file.created, file.source),
+            (datetime(2025, 5, 2, 10, 0, 0), DateSource::Xmp)
+        );
+
+        let file = read("xmp.GIF", xmp_gif(xmp))?.unwrap();
+        assert_eq!(
+            (file.created, file.source),
+            (datetime(2025, 5, 2, 10, 0, 0), DateSource::Xmp)
+        );
+
+        // XMP is searched only in WebP and GIF files
+        assert_eq!(read("xmp.txt", xmp_webp(xmp))?, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn read_pdf() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("scan.PDF");
+        std::fs::write(
+            &path,
+            crate::test_utils::pdf(Some("D:20250501121314+02'00'")),
+        )?;
+
+        let file = File::read(path, &ReadOptions::default())?.unwrap();
+        assert_eq!(
+            (file.created, file.source),
+            (datetime(2025, 5, 1, 12, 13, 14), DateSource::Pdf)
+        );
+        Ok(())
+    }
+
     #[test]
     fn cmp_by_path() {
         let created = datetime(2025, 5, 1, 10, 11, 12);
         let mut first_file = File {
             path: PathBuf::from("./some/path/1.jpg"),
             created,
+            source: DateSource::Exif,
+            offset: None,
         };
         let mut second_file = File {
             path: PathBuf::from("./some/path/2.jpg"),
             created,
+            source: DateSource::Exif,
+            offset: None,
         };
         assert_eq!(
             ByPath::<&File>(&first_file).cmp(&ByPath::<&File>(&second_file)),
@@ -246,15 +881,44 @@ mod tests {
         assert_eq!(first_file.path, PathBuf::from("./some/path/3.jpg"));
     }
 
+    #[test]
+    fn cmp_by_file_name() {
+        let file = |path: &str| File {
+            path: PathBuf::from(path),
+            created: datetime(2025, 5, 1, 10, 11, 12),
+            source: DateSource::Exif,
+            offset: None,
+        };
+        let cmp = |a: &File, b: &File| ByFileName(a).cmp(&ByFileName(b));
+
+        // Directories are ignored, unlike with ByPath
+        let (a, b) = (file("./b/1.jpg"), file("./a/2.jpg"));
+        assert_eq!(cmp(&a, &b), Ordering::Less);
+        assert_eq!(cmp(&b, &a), Ordering::Greater);
+        assert_eq!(ByPath(&a).cmp(&ByPath(&b)), Ordering::Greater);
+
+        // Identical names in different directories are ordered by path
+        let (a, b) = (file("./b/1.jpg"), file("./a/1.jpg"));
+        assert_eq!(cmp(&a, &b), Ordering::Greater);
+        assert_eq!(cmp(&b, &a), Ordering::Less);
+        assert!(ByFileName(&a) != ByFileName(&b));
+        assert!(ByFileName(&a) == ByFileName(&file("./b/1.jpg")));
+        assert_eq!(cmp(&a, &file("./b/1.jpg")), Ordering::Equal);
+    }
+
     #[test]
     fn cmp_by_date() {
         let mut first_file = File {
             path: PathBuf::new(),
             created: datetime(2025, 5, 1, 10, 11, 12),
+            source: DateSource::Exif,
+            offset: None,
         };
         let mut second_file = File {
             path: PathBuf::new(),
             created: datetime(2025, 5, 1, 10, 11, 13),
+            source: DateSource::Exif,
+            offset: None,
         };
 
         assert_eq!(
@@ -288,4 +952,57 @@ mod tests {
         }
         assert_eq!(first_file.created, datetime(2025, 5, 1, 10, 11, 13));
     }
+
+    #[test]
+    fn cmp_by_date_same_instant() {
+        let file = |path: &str| File {
+            path: PathBuf::from(path),
+            created: datetime(2025, 5, 1, 10, 11, 12),
+            source: DateSource::Exif,
+            offset: None,
+        };
+        let (a, b) = (file("./b/1.jpg"), file("./a/2.jpg"));
+
+        assert_eq!(ByCreatedDate(&a).cmp(&ByCreatedDate(&b)), Ordering::Greater);
+        assert_eq!(ByCreatedDate(&b).cmp(&ByCreatedDate(&a)), Ordering::Less);
+        assert!(ByCreatedDate(&a) != ByCreatedDate(&b));
+        assert!(ByCreatedDate(&a) == ByCreatedDate(&file("./b/1.jpg")));
+    }
+
+    #[test]
+    fn cmp_by_date_subsec_and_offset() {
+        let file = |created: NaiveDateTime, offset: Option<i32>| File {
+            path: PathBuf::new(),
+            created,
+            source: DateSource::Exif,
+            offset: offset.and_then(|hours| FixedOffset::east_opt(hours * 3600)),
+        };
+        let cmp = |a: &File, b: &File| ByCreatedDate(a).cmp(&ByCreatedDate(b));
+        let second = datetime(2025, 5, 1, 10, 0, 0);
+
+        // Burst shot within the same second
+        assert_eq!(
+            cmp(
+                &file(second.with_nanosecond(500_000_000).unwrap(), None),
+                &file(second.with_nanosecond(250_000_000).unwrap(), None)
+            ),
+            Ordering::Greater
+        );
+
+        // 10:00 in UTC+2 was taken before 09:00 in UTC
+        assert_eq!(
+            cmp(
+                &file(second, Some(2)),
+                &file(datetime(2025, 5, 1, 9, 0, 0), Some(0))
+            ),
+            Ordering::Less
+        );
+        assert_eq!(
+            cmp(
+                &file(second, Some(2)),
+                &file(datetime(2025, 5, 1, 8, 0, 0), None)
+            ),
+            Ordering::Equal
+        );
+    }
 }
@@ -0,0 +1,71 @@
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// File name patterns of messaging apps as `(prefix, date format, text following the date)`.
+///
+/// Patterns with a date only are assigned midnight of that day.
+const PATTERNS: [(&str, &str, &str); 5] = [
+    // WhatsApp, e.g. `IMG-20250501-WA0001.jpg`
+    ("IMG-", "%Y%m%d", "-WA"),
+    ("VID-", "%Y%m%d", "-WA"),
+    // Signal, e.g. `signal-2025-05-01-12-13-14-123.jpg`
+    ("signal-", "%Y-%m-%d-%H-%M-%S", ""),
+    // Older Signal, e.g. `signal-2025-05-01-121314.jpg`
+    ("signal-", "%Y-%m-%d-%H%M%S", ""),
+    // Telegram Desktop, e.g. `photo_2025-05-01_12-13-14.jpg`
+    ("photo_", "%Y-%m-%d_%H-%M-%S", ""),
+];
+
+/// Extracts the creation date from a file name created by a messaging app.
+///
+/// Returns `None` if the name does not match any of the supported patterns.
+pub fn parse(name: &str) -> Option<NaiveDateTime> {
+    PATTERNS.iter().find_map(|(prefix, format, suffix)| {
+        let rest = name.strip_prefix(prefix)?;
+        let (created, rest) = NaiveDateTime::parse_and_remainder(rest, format)
+            .or_else(|_| {
+                NaiveDate::parse_and_remainder(rest, format)
+                    .map(|(date, rest)| (date.and_time(Default::default()), rest))
+            })
+            .ok()?;
+        rest.starts_with(suffix).then_some(created)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_messaging_names() {
+        let date = |s| NaiveDateTime::from_str(s).ok();
+        assert_eq!(
+            parse("IMG-20250501-WA0001.jpg"),
+            date("2025-05-01T00:00:00")
+        );
+        assert_eq!(
+            parse("VID-20250502-WA0013.mp4"),
+            date("2025-05-02T00:00:00")
+        );
+        assert_eq!(
+            parse("signal-2025-05-01-12-13-14-123.jpg"),
+            date("2025-05-01T12:13:14")
+        );
+        assert_eq!(
+            parse("signal-2025-05-01-121314.jpg"),
+            date("2025-05-01T12:13:14")
+        );
+        assert_eq!(
+            parse("photo_2025-05-01_12-13-14.jpg"),
+            date("2025-05-01T12:13:14")
+        );
+    }
+
+    #[test]
+    fn parse_other_names() {
+        assert_eq!(parse("IMG_0001.jpg"), None);
+        assert_eq!(parse("IMG-20250501-0001.jpg"), None);
+        assert_eq!(parse("IMG-20251301-WA0001.jpg"), None);
+        assert_eq!(parse("2025-05-01 Trip 1.jpg"), None);
+    }
+}
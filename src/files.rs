@@ -1,8 +1,21 @@
-use super::{file::File, files_interval::FilesInterval};
-use crate::file::ByCreatedDate;
+use super::{
+    file::File, files_interval::FilesInterval, name_template::NameTemplate,
+    stable_index::StableIndex,
+};
+use crate::error::PhotoDaterError;
+use crate::file::ByPath;
 use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+use chrono_tz::Tz;
+use clap::ValueEnum;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use rayon::prelude::*;
 use std::{
-    fs, io,
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Display,
+    fs,
+    io::{self, BufRead},
     ops::{Deref, DerefMut},
     path::{Path, PathBuf},
 };
@@ -10,35 +23,522 @@ use std::{
 /// Type alias for a collection of renamed files with their new paths.
 pub type RenamedFiles<'a> = Vec<RenamedFile<'a>>;
 
+/// A file left out of a plan paired with the kept file of identical content.
+pub type Duplicate<'a> = (&'a File, &'a File);
+
 /// Represents a file and its proposed new path for rename/move operations.
 ///
 /// This structure pairs an original file reference with a new filesystem path.
 #[derive(Debug, PartialEq, Eq)]
 pub struct RenamedFile<'a>(pub &'a File, pub PathBuf);
 
+/// Finds places where sorting renamed files alphabetically by their new file name
+/// breaks the chronological order of the original files.
+///
+/// File browsers usually sort by name, so with insufficient zero-padding
+/// (e.g. `photo 10.jpg` sorting before `photo 2.jpg`) the photos would no
+/// longer be shown in the order they were taken.
+///
+/// # Returns
+///
+/// Pairs of adjacent renames, in alphabetical order of the new names, where the
+/// second file was created before the first one.
+pub fn order_breaks<'a>(
+    renamed: &'a [RenamedFile<'a>],
+) -> Vec<(&'a RenamedFile<'a>, &'a RenamedFile<'a>)> {
+    let mut sorted = renamed.iter().collect::<Vec<_>>();
+    sorted.sort_by(|a, b| a.1.file_name().cmp(&b.1.file_name()));
+    sorted
+        .windows(2)
+        .filter(|pair| pair[1].0.created < pair[0].0.created)
+        .map(|pair| (pair[0], pair[1]))
+        .collect()
+}
+
+/// Checks that renaming would not overwrite any file.
+///
+/// # Errors
+///
+/// Returns an error identifying the conflict if two files would get the same
+/// new path, or if a new path already exists and is not renamed away by the
+/// same batch.
+pub fn check_collisions(renamed: &[RenamedFile]) -> Result<()> {
+    let sources = renamed
+        .iter()
+        .map(|RenamedFile(file, _)| file.path.as_path())
+        .collect::<HashSet<_>>();
+    let mut targets = HashMap::<&Path, &Path>::new();
+    renamed.iter().try_for_each(|RenamedFile(file, new_path)| {
+        if let Some(other) = targets.insert(new_path, &file.path) {
+            return Err(anyhow!(
+                "Both {other:?} and {:?} would be renamed to {new_path:?}",
+                file.path
+            ));
+        }
+        if new_path.exists() && !sources.contains(new_path.as_path()) {
+            return Err(anyhow!(
+                "Renaming {:?} would overwrite existing file {new_path:?}",
+                file.path
+            ));
+        }
+        Ok(())
+    })
+}
+
+/// Converts extensions of the new paths to lowercase, leaving the rest of the names untouched.
+///
+/// # Errors
+///
+/// Returns an error if a new path collides, see [`check_collisions`]. Extensions that are
+/// not valid UTF-8 get only their ASCII letters lowercased.
+pub fn lowercase_extensions(renamed: RenamedFiles) -> Result<RenamedFiles> {
+    let renamed = renamed
+        .into_iter()
+        .map(|RenamedFile(file, new_path)| {
+            // Dots in the base name are no extension when the original file had none
+            let extension = file.path.extension().and(new_path.extension()).map(|s| {
+                s.to_str()
+                    .map_or_else(|| s.to_ascii_lowercase(), |s| s.to_lowercase().into())
+            });
+            Ok(RenamedFile(
+                file,
+                match extension {
+                    Some(extension) => new_path.with_extension(extension),
+                    None => new_path,
+                },
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    check_collisions(&renamed)?;
+    Ok(renamed)
+}
+
+/// Appends a numeric suffix to new paths already taken by another file of the plan,
+/// or by an existing file, e.g. `IMG_001.jpg` becomes `IMG_001_1.jpg`.
+///
+/// Files that stay in place keep their paths.
+fn number_collisions(renamed: RenamedFiles) -> RenamedFiles {
+    let numbered = |path: &Path, n: usize| {
+        if n == 0 {
+            return path.to_path_buf();
+        }
+        let mut name = path.file_stem().unwrap_or_default().to_os_string();
+        name.push(format!("_{n}"));
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        path.with_file_name(name)
+    };
+    let mut taken = renamed
+        .iter()
+        .filter(|RenamedFile(file, new_path)| file.path == *new_path)
+        .map(|RenamedFile(_, new_path)| new_path.clone())
+        .collect::<HashSet<_>>();
+    renamed
+        .into_iter()
+        .map(|RenamedFile(file, new_path)| {
+            if file.path == new_path {
+                return RenamedFile(file, new_path);
+            }
+            let new_path = (0..)
+                .map(|n| numbered(&new_path, n))
+                .find(|path| !taken.contains(path) && !path.exists())
+                .unwrap();
+            taken.insert(new_path.clone());
+            RenamedFile(file, new_path)
+        })
+        .collect()
+}
+
+/// Removes files whose content is identical to another file of the same group from a move plan.
+///
+/// Files are compared by size and hash of their content first, candidates with
+/// equal hash are then compared byte by byte. The first file of each set of
+/// identical files stays in the plan.
+///
+/// # Returns
+///
+/// The plan without duplicates and the removed files, each paired with the
+/// file of identical content that stays in the plan.
+///
+/// # Errors
+///
+/// Returns an error if a file cannot be read.
+pub fn dedupe_content(
+    plan: Vec<RenamedFiles<'_>>,
+) -> Result<(Vec<RenamedFiles<'_>>, Vec<Duplicate<'_>>)> {
+    let mut duplicates = Vec::new();
+    let plan = plan
+        .into_iter()
+        .map(|group| {
+            let mut kept = HashMap::<(u64, u64), Vec<&File>>::new();
+            let mut deduped = Vec::new();
+            for renamed in group {
+                let file = renamed.0;
+                let candidates = kept.entry(file.hash()?).or_default();
+                let mut original = None;
+                if !candidates.is_empty() {
+                    let content = fs::read(&file.path)?;
+                    for other in candidates.iter() {
+                        if fs::read(&other.path)? == content {
+                            original = Some(*other);
+                            break;
+                        }
+                    }
+                }
+                match original {
+                    Some(original) => duplicates.push((file, original)),
+                    None => {
+                        candidates.push(file);
+                        deduped.push(renamed);
+                    }
+                }
+            }
+            Ok(deduped)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((plan, duplicates))
+}
+
+/// Capture times of the first and the last photo taken on a day.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DaySpan {
+    pub day: NaiveDate,
+    pub first: NaiveTime,
+    pub last: NaiveTime,
+}
+
+impl DaySpan {
+    /// Time elapsed between the first and the last photo of the day.
+    pub fn duration(&self) -> TimeDelta {
+        self.last - self.first
+    }
+}
+
+/// Describes a planned move whose outcome on disk does not match the plan.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MoveDiscrepancy {
+    /// No dated file was found at the planned destination
+    Missing(PathBuf),
+    /// A file exists at the planned destination but has a different creation date
+    DateMismatch {
+        path: PathBuf,
+        expected: NaiveDateTime,
+        found: NaiveDateTime,
+    },
+}
+
+impl Display for MoveDiscrepancy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveDiscrepancy::Missing(path) => write!(f, "{path:?} is missing"),
+            MoveDiscrepancy::DateMismatch {
+                path,
+                expected,
+                found,
+            } => write!(f, "{path:?} was created {found}, expected {expected}"),
+        }
+    }
+}
+
+/// Outcome of a single planned rename or move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The file would get a new path
+    Renamed,
+    /// The new path is the same as the current one
+    Unchanged,
+    /// The new path is already taken, either by another planned file or by an
+    /// existing file that is not moved away by the plan
+    Conflict,
+}
+
+impl Outcome {
+    /// Classifies each planned operation, keeping the order of the plan.
+    pub fn classify(plan: &[RenamedFile]) -> Vec<Outcome> {
+        let mut targets = HashMap::<&Path, usize>::new();
+        plan.iter()
+            .for_each(|RenamedFile(_, new_path)| *targets.entry(new_path).or_default() += 1);
+        let sources = plan
+            .iter()
+            .map(|RenamedFile(file, _)| file.path.as_path())
+            .collect::<HashSet<_>>();
+        plan.iter()
+            .map(|RenamedFile(file, new_path)| {
+                if file.path == *new_path {
+                    Outcome::Unchanged
+                } else if targets[new_path.as_path()] > 1
+                    || (new_path.exists() && !sources.contains(new_path.as_path()))
+                {
+                    Outcome::Conflict
+                } else {
+                    Outcome::Renamed
+                }
+            })
+            .collect()
+    }
+}
+
+/// Number of planned operations in each [`Outcome`] category.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PlanSummary {
+    pub renamed: usize,
+    pub unchanged: usize,
+    pub conflicts: usize,
+}
+
+impl PlanSummary {
+    /// Counts the outcomes of the planned operations.
+    pub fn new(plan: &[RenamedFile]) -> Self {
+        Outcome::classify(plan)
+            .into_iter()
+            .fold(Self::default(), |mut summary, outcome| {
+                match outcome {
+                    Outcome::Renamed => summary.renamed += 1,
+                    Outcome::Unchanged => summary.unchanged += 1,
+                    Outcome::Conflict => summary.conflicts += 1,
+                }
+                summary
+            })
+    }
+}
+
+impl Display for PlanSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} to rename, {} unchanged, {} in conflict",
+            self.renamed, self.unchanged, self.conflicts
+        )
+    }
+}
+
+/// Totals of a plan reported once it is applied or previewed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlanCount {
+    /// Files that get a new path
+    pub files: usize,
+    /// Distinct directories the files end up in
+    pub directories: usize,
+    /// Files left in place
+    pub skipped: usize,
+}
+
+impl PlanCount {
+    /// Counts files of `plan` that get a new path and directories they end up in.
+    /// Files keeping their path or whose new path has no parent directory are skipped.
+    pub fn new(plan: &[RenamedFile]) -> Self {
+        let (changed, skipped): (Vec<_>, Vec<_>) =
+            plan.iter().partition(|RenamedFile(file, new_path)| {
+                file.path != *new_path && new_path.parent().is_some()
+            });
+        Self {
+            files: changed.len(),
+            directories: changed
+                .iter()
+                .filter_map(|RenamedFile(_, new_path)| new_path.parent())
+                .collect::<HashSet<_>>()
+                .len(),
+            skipped: skipped.len(),
+        }
+    }
+
+    /// Describes the totals of the operation given by its base form and past tense,
+    /// e.g. `Would move 487 files into 12 directories (3 skipped)` for a dry run.
+    pub fn describe(&self, (verb, past): (&str, &str), dry_run: bool) -> String {
+        let plural = |count: usize, one: &str, many: &str| {
+            format!("{count} {}", if count == 1 { one } else { many })
+        };
+        format!(
+            "{} {} into {} ({} skipped)",
+            if dry_run {
+                format!("Would {verb}")
+            } else {
+                past.to_string()
+            },
+            plural(self.files, "file", "files"),
+            plural(self.directories, "directory", "directories"),
+            self.skipped
+        )
+    }
+}
+
+/// Nesting of date subdirectories created by [`Files::move_by_date_tree`].
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateTree {
+    /// `YYYY`
+    #[value(name = "year")]
+    Year,
+    /// `YYYY/MM`
+    #[value(name = "year/month")]
+    YearMonth,
+    /// `YYYY/MM/DD`
+    #[value(name = "year/month/day")]
+    YearMonthDay,
+}
+
+impl DateTree {
+    /// Format strings of the subdirectory names, one for each level.
+    fn formats(&self) -> &'static [&'static str] {
+        match self {
+            DateTree::Year => &["%Y"],
+            DateTree::YearMonth => &["%Y", "%m"],
+            DateTree::YearMonthDay => &["%Y", "%m", "%d"],
+        }
+    }
+}
+
+/// Name of the file with gitignore-style patterns of paths skipped by [`Files::read`],
+/// looked up in the read directory.
+pub const IGNORE_NAME: &str = ".photodaterignore";
+
+/// Options controlling how files and their creation dates are read.
+#[derive(Debug, Default, Clone)]
+pub struct ReadOptions {
+    /// Number of directory levels to read. `Some(1)` reads only files directly
+    /// inside the directory, `None` reads the whole directory tree.
+    pub max_depth: Option<usize>,
+    /// When set, EXIF dates are treated as UTC and converted to this time zone.
+    /// Dates with a known offset, including ones given by [`ReadOptions::timezone`],
+    /// are converted using that offset instead.
+    pub utc_to: Option<Tz>,
+    /// Time zone the camera clock was set to. EXIF dates without an offset get the
+    /// offset of this zone at their local time.
+    pub timezone: Option<Tz>,
+    /// When set, files without EXIF date take the date from messaging app file
+    /// names such as `IMG-20250501-WA0001.jpg`.
+    pub name_dates: bool,
+    /// Number of threads reading files in parallel, `None` uses one thread per CPU.
+    pub jobs: Option<usize>,
+    /// When not empty, only files with one of these extensions (compared
+    /// case-insensitively) are read.
+    pub extensions: Vec<String>,
+    /// Read files and directories behind symbolic links, otherwise symbolic links are skipped.
+    pub follow_symlinks: bool,
+    /// Read hidden files and directories (names starting with `.`, e.g. `.thumbnails`),
+    /// otherwise they are skipped.
+    pub dot_files: bool,
+    /// Collect files that cannot be read (e.g. an unparseable EXIF date) into
+    /// [`Files::errors`] instead of failing the whole read.
+    pub skip_errors: bool,
+}
+
+impl ReadOptions {
+    /// Returns `true` if the file passes the extension filter.
+    fn accepts(&self, path: &Path) -> bool {
+        let extensions = self
+            .extensions
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        extensions.is_empty() || File::has_extension(path, &extensions)
+    }
+}
+
+/// Compiles patterns of the [`IGNORE_NAME`] file in `directory`, matching nothing if
+/// there is no such file.
+fn read_ignore(directory: &Path) -> io::Result<Gitignore> {
+    let path = directory.join(IGNORE_NAME);
+    if !path.is_file() {
+        return Ok(Gitignore::empty());
+    }
+    let mut builder = GitignoreBuilder::new(directory);
+    if let Some(e) = builder.add(&path) {
+        return Err(io::Error::other(e));
+    }
+    builder.build().map_err(io::Error::other)
+}
+
+/// Reads a newline-delimited list of file paths, e.g. the output of `find` or `fd`.
+/// Empty lines are skipped and Windows line endings are accepted. On Unix, lines are
+/// taken as raw bytes, so names that are not valid UTF-8 are read as well.
+///
+/// # Errors
+///
+/// Returns an error if reading fails, or on other platforms if a line is not valid UTF-8.
+pub fn read_path_list(reader: impl BufRead) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for line in reader.split(b'\n') {
+        let line = line?;
+        let path = line.strip_suffix(b"\r").unwrap_or(&line);
+        if !path.is_empty() {
+            paths.push(path_from_bytes(path)?);
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    Ok(PathBuf::from(OsStr::from_bytes(bytes)))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    std::str::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Returns the number of digits of `number`, used to pad sequence numbers up to it
+/// (e.g. `3` for `100`, so numbers are written as `001` to `100`).
+pub fn number_width(number: usize) -> usize {
+    number.checked_ilog10().unwrap_or(0) as usize + 1
+}
+
 /// A collection of files that provides various operations for file management and organization.
 ///
 /// This struct wraps a `Vec<File>` and provides methods for reading files from directories,
 /// grouping files by date, and organizing file operations.
 #[derive(Debug)]
-pub struct Files(Vec<File>);
+pub struct Files {
+    files: Vec<File>,
+    /// Paths of files that were read but had no creation date
+    skipped: Vec<PathBuf>,
+    /// Files that failed to read, collected with [`ReadOptions::skip_errors`]
+    errors: Vec<(PathBuf, PhotoDaterError)>,
+}
 
 impl Files {
     /// Creates a new Files collection from a vector of files.
-    #[cfg(test)]
     pub fn new(files: Vec<File>) -> Self {
-        Self(files)
+        Self {
+            files,
+            skipped: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Splits files by the directory they are currently in, so each part can be
+    /// processed independently of the others.
+    ///
+    /// # Returns
+    ///
+    /// Parent directories sorted by path, each with the files directly inside it.
+    pub fn group_by_parent(&self) -> Vec<(PathBuf, Files)> {
+        self.group_by(|file| file.path.parent().unwrap_or(Path::new("")).to_path_buf())
+            .into_iter()
+            .map(|(parent, files)| (parent, Files::new(files.into_iter().cloned().collect())))
+            .collect()
     }
 
     /// Recursively reads all files from the specified directory path.
     ///
     /// This method traverses the directory tree starting from the given path,
-    /// collecting all files found in subdirectories. Files without EXIF data or
-    /// creation dates are skipped.
+    /// collecting all files found in subdirectories, and then reads their creation
+    /// dates in parallel. Files are kept in the traversal order. Files without EXIF
+    /// data or creation dates are skipped, their paths are available via [`Files::skipped`].
+    ///
+    /// Files and directories matching patterns of an [`IGNORE_NAME`] file in `path` are
+    /// left out, ignored directories are not traversed at all.
     ///
     /// # Arguments
     ///
     /// * `path` - A path-like object that references the directory to read from
+    /// * `options` - Options limiting the traversal depth and adjusting how
+    ///   creation dates are read, see [`ReadOptions`]
     ///
     /// # Errors
     ///
@@ -46,30 +546,128 @@ impl Files {
     /// - The specified path cannot be read
     /// - File system permissions prevent access to files or directories
     /// - I/O errors occur during directory traversal
-    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
-        /// Recursive helper function to read files from a directory.
-        fn read_dir(path: impl AsRef<Path>) -> Result<Vec<File>> {
-            fs::read_dir(path.as_ref())?;
-            Ok(fs::read_dir(path.as_ref())?
+    /// - The [`IGNORE_NAME`] file cannot be read or contains an invalid pattern
+    pub fn read(path: impl AsRef<Path>, options: &ReadOptions) -> Result<Self, PhotoDaterError> {
+        /// Recursive helper function collecting file paths from a directory at the given depth.
+        ///
+        /// Directories already visited (compared by canonical path) are skipped, so
+        /// symlinks pointing back to an ancestor do not cause infinite recursion.
+        fn read_dir(
+            path: impl AsRef<Path>,
+            depth: usize,
+            options: &ReadOptions,
+            ignore: &Gitignore,
+            visited: &mut HashSet<PathBuf>,
+            paths: &mut Vec<PathBuf>,
+        ) -> Result<(), PhotoDaterError> {
+            if !visited.insert(fs::canonicalize(path.as_ref())?) {
+                return Ok(());
+            }
+            fs::read_dir(path.as_ref())?
                 .collect::<io::Result<Vec<_>>>()?
                 .into_iter()
                 .map(|e| e.path())
-                .map(|p| -> Result<Vec<File>> {
-                    if p.is_file() {
-                        Ok(File::read(p)?.map(|f| vec![f]).unwrap_or_default())
-                    } else if p.is_dir() {
-                        read_dir(p)
+                .filter(|p| {
+                    options.dot_files
+                        || !p
+                            .file_name()
+                            .is_some_and(|name| name.as_encoded_bytes().starts_with(b"."))
+                })
+                .try_for_each(|p| {
+                    let metadata = if options.follow_symlinks {
+                        // Broken symbolic links are skipped like any other link
+                        fs::metadata(&p).or_else(|_| fs::symlink_metadata(&p))?
                     } else {
-                        Ok(vec![])
+                        fs::symlink_metadata(&p)?
+                    };
+                    if ignore.matched(&p, metadata.is_dir()).is_ignore() {
+                        return Ok(());
                     }
+                    if metadata.is_file() {
+                        paths.push(p);
+                    } else if metadata.is_dir() && options.max_depth.is_none_or(|max| depth < max) {
+                        read_dir(p, depth + 1, options, ignore, visited, paths)?;
+                    }
+                    Ok(())
                 })
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .flatten()
-                .collect::<Vec<_>>())
         }
 
-        Ok(Self(read_dir(path)?))
+        let ignore = read_ignore(path.as_ref())?;
+        let mut paths = Vec::new();
+        read_dir(path, 1, options, &ignore, &mut HashSet::new(), &mut paths)?;
+        Self::from_paths(paths, options)
+    }
+
+    /// Reads creation dates of the given files in parallel, e.g. of a list produced by
+    /// `find`, see [`read_path_list`]. Files are kept in the given order, files without
+    /// creation dates are skipped like in [`Files::read`] and files not passing
+    /// [`ReadOptions::extensions`] are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read, unless [`ReadOptions::skip_errors`] is set.
+    pub fn from_paths(
+        paths: impl IntoIterator<Item = PathBuf>,
+        options: &ReadOptions,
+    ) -> Result<Self, PhotoDaterError> {
+        let paths = paths
+            .into_iter()
+            .filter(|p| options.accepts(p))
+            .collect::<Vec<_>>();
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(options.jobs.unwrap_or(0))
+            .build()
+            .map_err(io::Error::other)?;
+        let read = pool.install(|| {
+            paths
+                .into_par_iter()
+                .map(|p| (File::read(p.clone(), options), p))
+                .collect::<Vec<_>>()
+        });
+
+        let mut files = Self::new(Vec::new());
+        for (file, p) in read {
+            match file {
+                Ok(Some(file)) => files.files.push(file),
+                Ok(None) => files.skipped.push(p),
+                Err(e) if options.skip_errors => files.errors.push((p, e)),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(files)
+    }
+
+    /// Returns paths of files that were skipped while reading because they
+    /// have no EXIF creation date.
+    pub fn skipped(&self) -> &[PathBuf] {
+        &self.skipped
+    }
+
+    /// Returns files that failed to read, with the reason, when read with
+    /// [`ReadOptions::skip_errors`].
+    pub fn errors(&self) -> &[(PathBuf, PhotoDaterError)] {
+        &self.errors
+    }
+
+    /// Moves all files, including the skipped and failed ones, of `other` into this collection.
+    pub fn append(&mut self, mut other: Files) {
+        self.files.append(&mut other.files);
+        self.skipped.append(&mut other.skipped);
+        self.errors.append(&mut other.errors);
+    }
+
+    /// Keeps only files created strictly after the given date.
+    pub fn retain_after(&mut self, after: NaiveDateTime) {
+        self.files.retain(|file| file.created > after);
+    }
+
+    /// Keeps only files created between the given days, both inclusive.
+    /// A missing bound does not limit the range.
+    pub fn retain_range(&mut self, from: Option<NaiveDate>, to: Option<NaiveDate>) {
+        self.files.retain(|file| {
+            from.is_none_or(|from| file.created.date() >= from)
+                && to.is_none_or(|to| file.created.date() <= to)
+        });
     }
 
     /// This generic method allows sorting files by any ordering wrapper type
@@ -79,29 +677,49 @@ impl Files {
     ///
     /// * `T` - The ordering wrapper type (e.g., `ByPath<&File>`, `ByCreatedDate<&File>`)
     ///
+    /// # Arguments
+    ///
+    /// * `reverse` - Return the sorted files in reverse order, e.g. newest first
+    ///
     /// # Returns
     ///
     /// A vector of file references sorted according to the specified ordering criterion.
-    pub fn get_sorted<'a, T>(&'a self) -> Vec<&'a File>
+    pub fn get_sorted<'a, T>(&'a self, reverse: bool) -> Vec<&'a File>
     where
         T: Deref<Target = &'a File> + From<&'a File> + Ord,
     {
         let mut files: Vec<_> = self.iter().map(T::from).collect();
         files.sort();
+        if reverse {
+            files.reverse();
+        }
         files.into_iter().map(|f| *f).collect()
     }
 
+    /// Returns references to all files sorted with the given comparison function.
+    ///
+    /// The sort is stable, so files comparing as equal keep their original order.
+    pub fn get_sorted_by<F>(&self, mut compare: F) -> Vec<&File>
+    where
+        F: FnMut(&File, &File) -> Ordering,
+    {
+        let mut files: Vec<_> = self.iter().collect();
+        files.sort_by(|a, b| compare(a, b));
+        files
+    }
+
     /// Calculates the time interval spanning from the oldest to the newest file.
     /// Returns `None` if the collection is empty.
+    ///
+    /// Both ends are local creation times, compared as recorded like the days files
+    /// are grouped by, so the interval never ends before it starts even if the files
+    /// were taken in different time zones.
     pub fn interval(&self) -> Option<FilesInterval> {
         match (
-            self.iter().map(ByCreatedDate).min(),
-            self.iter().map(ByCreatedDate).max(),
+            self.iter().map(|file| file.created).min(),
+            self.iter().map(|file| file.created).max(),
         ) {
-            (Some(from), Some(to)) => Some(FilesInterval {
-                from: from.created,
-                to: to.created,
-            }),
+            (Some(from), Some(to)) => Some(FilesInterval { from, to }),
             _ => None,
         }
     }
@@ -120,125 +738,594 @@ impl Files {
     /// * `name` - The base name to use for renaming files
     /// * `digits` - Optional number of digits for zero-padding the sequential numbers. If `None`,
     ///   automatically calculates based on the total number of files (e.g., 100 files = 3 digits)
+    /// * `template` - Layout of the new file names, see [`NameTemplate`]
     ///
     /// # Errors
     ///
-    /// Returns an error if file extensions contain non-UTF-8 characters.
+    /// Returns an error if a new path collides, see [`check_collisions`], or if `digits`
+    /// are fewer than the highest number has, so all numbers have the same width.
     ///
     /// # Examples
     ///
-    /// For files "b.jpg", "a.png", "c" with base name "photo" sorted by path:
+    /// For files "b.jpg", "a.png", "c" with base name "photo" sorted by path and
+    /// the default template:
     /// - "a.png" → "photo 0001.png"  
     /// - "b.jpg" → "photo 0002.jpg"
     /// - "c" → "photo 0003"
+    #[cfg(test)]
     pub fn rename_files<'a, T>(
         &'a self,
         name: &str,
         digits: Option<usize>,
+        template: &NameTemplate,
+    ) -> Result<RenamedFiles<'a>>
+    where
+        T: Deref<Target = &'a File> + From<&'a File> + Ord,
+    {
+        self.rename_files_stable::<T>(name, digits, template, &mut StableIndex::default(), false)
+    }
+
+    /// Creates a list of rename operations like [`Files::rename_files`], reusing sequence
+    /// numbers from a previous run.
+    ///
+    /// Files whose current file name is present in `index` keep their number. Remaining
+    /// files get numbers above the highest used one, but at least the start of `index`, in
+    /// the order given by `T` (reversed if `reverse` is set), and are added to `index` so
+    /// it can be saved for the next run. Numbers are padded to the width of the highest one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a new path collides, see [`check_collisions`], or if `digits`
+    /// are too few for the highest number. File names that are not valid UTF-8 are
+    /// stored in `index` with invalid characters replaced.
+    pub fn rename_files_stable<'a, T>(
+        &'a self,
+        name: &str,
+        digits: Option<usize>,
+        template: &NameTemplate,
+        index: &mut StableIndex,
+        reverse: bool,
     ) -> Result<RenamedFiles<'a>>
     where
         T: Deref<Target = &'a File> + From<&'a File> + Ord,
     {
-        let digits = digits.unwrap_or((self.len() as f64).log10().floor() as usize + 1);
-        self.get_sorted::<T>()
+        let numbered = self
+            .get_sorted::<T>(reverse)
+            .into_iter()
+            .map(|file| {
+                let file_name = file
+                    .path
+                    .file_name()
+                    .ok_or(anyhow!("Cannot get file name from {:?}", file.path))?
+                    .to_string_lossy();
+                let i = index
+                    .get(&file_name)
+                    .unwrap_or_else(|| index.assign(&file_name));
+                Ok((i, file))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let width = number_width(index.max());
+        let digits = match digits {
+            Some(digits) if digits < width => {
+                return Err(anyhow!(
+                    "{digits} digits are not enough for number {}, at least {width} are needed",
+                    index.max()
+                ))
+            }
+            Some(digits) => digits,
+            None => width,
+        };
+        let renamed = numbered
             .into_iter()
-            .enumerate()
-            .map(|(i, file)| (i + 1, file))
             .map(|(i, file)| {
-                let new_path = file.path.with_file_name(
-                    file.path
-                        .extension()
-                        .map(|s| s.to_str().ok_or(anyhow!("Non UTF-8 file suffix.")))
-                        .transpose()?
-                        .map(|s| format!("{name} {i:0digits$}.{s}"))
-                        .unwrap_or(format!("{name} {i:0digits$}")),
-                );
-                Ok(RenamedFile(file, new_path))
+                let new_path = file.path.with_file_name(template.render(
+                    name,
+                    i,
+                    digits,
+                    &file.created,
+                    file.path.extension(),
+                ));
+                RenamedFile(file, new_path)
+            })
+            .collect::<Vec<_>>();
+        check_collisions(&renamed)?;
+        Ok(renamed)
+    }
+
+    /// Renames files on disk according to the given plan.
+    ///
+    /// Files are first moved to temporary names in their directory and only then to
+    /// their new paths, so the plan may be any permutation of existing names (e.g.
+    /// `2.jpg` → `1.jpg` and `1.jpg` → `2.jpg`) without overwriting a file that is
+    /// yet to be renamed. Files whose new path equals the current one are left alone.
+    /// Missing parent directories of the new paths are created before any file is
+    /// renamed, so the plan may also move files to new directories, e.g. one from
+    /// [`Files::move_by_days`].
+    ///
+    /// The plan can be changed before it is applied, e.g. by dropping some entries.
+    ///
+    /// `on_rename` is called with the old and new path after every rename done
+    /// on disk, including the moves to and from the temporary names and the moves
+    /// back on failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a parent of a new path is not a directory, without renaming
+    /// anything, or if any rename or `on_rename` fails. On failure, files renamed so
+    /// far are moved back and the created directories are removed, as far as possible.
+    pub fn apply_renames<F>(renamed: &[RenamedFile], mut on_rename: F) -> Result<()>
+    where
+        F: FnMut(&Path, &Path) -> Result<()>,
+    {
+        let renamed = renamed
+            .iter()
+            .filter(|RenamedFile(file, new_path)| file.path != *new_path)
+            .collect::<Vec<_>>();
+        let parents = renamed
+            .iter()
+            .filter_map(|RenamedFile(_, new_path)| new_path.parent())
+            .collect::<HashSet<_>>();
+        if let Some(parent) = parents.iter().find(|parent| {
+            parent
+                .ancestors()
+                .find(|dir| dir.as_os_str().is_empty() || dir.exists())
+                .is_some_and(|dir| !dir.as_os_str().is_empty() && !dir.is_dir())
+        }) {
+            return Err(anyhow!(
+                "Cannot move files into {parent:?}, it is not a directory"
+            ));
+        }
+        // Ordered so parents come before their subdirectories
+        let created = parents
+            .iter()
+            .flat_map(|parent| {
+                parent
+                    .ancestors()
+                    .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            })
+            .collect::<BTreeSet<_>>();
+        let mut done = Vec::new();
+        let result = Self::rename_through_tmp(&renamed, &created, &mut done, &mut on_rename);
+        if result.is_err() {
+            done.iter().rev().for_each(|(from, to)| {
+                if fs::rename(to, from).is_ok() {
+                    // The original error is reported, not a failure to record the move back
+                    let _ = on_rename(to, from);
+                }
+            });
+            created.iter().rev().for_each(|dir| {
+                let _ = fs::remove_dir(dir);
+            });
+        }
+        result
+    }
+
+    /// Creates the directories and renames files for [`Files::apply_renames`],
+    /// pushing every rename done on disk to `done`.
+    fn rename_through_tmp<F>(
+        renamed: &[&RenamedFile],
+        created: &BTreeSet<&Path>,
+        done: &mut Vec<(PathBuf, PathBuf)>,
+        on_rename: &mut F,
+    ) -> Result<()>
+    where
+        F: FnMut(&Path, &Path) -> Result<()>,
+    {
+        created.iter().try_for_each(fs::create_dir)?;
+        let mut rename = |from: &Path, to: &Path| -> Result<()> {
+            fs::rename(from, to)?;
+            done.push((from.to_path_buf(), to.to_path_buf()));
+            on_rename(from, to)
+        };
+        let renamed = renamed
+            .iter()
+            .enumerate()
+            .map(|(i, RenamedFile(file, new_path))| {
+                let tmp = file
+                    .path
+                    .with_file_name(format!(".photo-dater-tmp-{}-{i}", std::process::id()));
+                rename(&file.path, &tmp)?;
+                Ok((tmp, new_path))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        renamed
+            .into_iter()
+            .try_for_each(|(tmp, new_path)| rename(&tmp, new_path))
+    }
+
+    /// Groups files by a key computed for each of them, e.g. their creation month.
+    ///
+    /// # Returns
+    ///
+    /// Distinct keys in ascending order, each with the files having that key in
+    /// their current order. Every returned group contains at least one file.
+    pub fn group_by<K, F>(&self, key: F) -> Vec<(K, Vec<&File>)>
+    where
+        K: Ord,
+        F: Fn(&File) -> K,
+    {
+        self.files
+            .iter()
+            .fold(BTreeMap::<K, Vec<&File>>::new(), |mut groups, file| {
+                groups.entry(key(file)).or_default().push(file);
+                groups
             })
+            .into_iter()
             .collect()
     }
 
     /// Groups files by their creation date, with each group containing files from the same day.
     ///
-    /// Files are sorted by creation date and then grouped into vectors where each
-    /// vector contains all files created on the same calendar day.
+    /// Groups are ordered by day and files within each group by creation date,
+    /// see [`Files::group_by`]. Every returned group contains at least one file.
     pub fn group_by_days(&self) -> Vec<Vec<&File>> {
-        let files = self.get_sorted::<ByCreatedDate<&File>>();
-        let first_created = match files.first() {
-            Some(file) => file.created.date(),
-            None => return Vec::new(),
-        };
-        let (_, last_group, mut ret) = files.into_iter().fold(
-            (first_created, Vec::new(), Vec::new()),
-            |(mut last_created, mut group, mut acc), file| {
-                let created = file.created.date();
-                if last_created != created {
-                    last_created = created;
-                    acc.push(group);
-                    group = Vec::new();
-                }
-                group.push(file);
-                (last_created, group, acc)
-            },
-        );
-        ret.push(last_group);
-        ret
+        // The day shown by the camera, not the UTC one, so a trip keeps its local days
+        self.group_by(|file| file.created.date())
+            .into_iter()
+            .map(|(_day, mut files)| {
+                files.sort_by_key(|file| file.created);
+                files
+            })
+            .collect()
     }
 
-    /// This method groups files by their creation date and generates new paths
-    /// where each file would be moved to a subdirectory named after its creation date
-    /// (formatted as "YYYY-MM-DD") within the same parent directory.
+    /// Finds adjacent files, in path order, whose creation date goes backwards.
+    ///
+    /// Within a burst or timelapse, files named later should never be created
+    /// earlier. Such a pair usually indicates a reset or changed camera clock.
     ///
     /// # Returns
     ///
-    /// A vector of vectors, where each inner vector represents a day's worth of files
-    /// and contains `RenamedFile` instances with original file references and new paths.
-    /// Files that cannot generate valid new paths (e.g., files without parent directories
-    /// or file names) are filtered out.
+    /// Pairs of `(previous, next)` files where `next` was created before `previous`.
+    pub fn clock_jumps(&self) -> Vec<(&File, &File)> {
+        let files = self.get_sorted::<ByPath<&File>>(false);
+        files
+            .windows(2)
+            .filter(|pair| pair[1].created < pair[0].created)
+            .map(|pair| (pair[0], pair[1]))
+            .collect()
+    }
+
+    /// Finds files whose creation date differs from their filesystem modification
+    /// time (see [`File::modified`]) by more than `max_drift`.
     ///
-    /// # Examples
+    /// A large difference usually indicates a wrongly set camera clock.
     ///
-    /// For a file "/photos/IMG_001.jpg" created on 2025-05-01:
-    /// - New path would be "/photos/2025-05-01/IMG_001.jpg"
-    pub fn move_by_days(&self) -> Vec<RenamedFiles<'_>> {
-        self.group_by_days()
+    /// # Returns
+    ///
+    /// Pairs of a file and its modification time, in path order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the modification time of a file cannot be read.
+    pub fn drifted(&self, max_drift: TimeDelta) -> io::Result<Vec<(&File, NaiveDateTime)>> {
+        self.get_sorted::<ByPath<&File>>(false)
             .into_iter()
-            .map(|group| {
-                group
-                    .into_iter()
-                    .filter_map(|file| {
-                        file.path
-                            .parent()
-                            .map(|parent| parent.join(file.created.format("%Y-%m-%d").to_string()))
-                            .and_then(|path| Some(path.join(file.path.file_name()?)))
-                            .map(|new_path| RenamedFile(file, new_path))
-                    })
-                    .collect()
+            .map(|file| Ok((file, file.modified()?)))
+            .filter(|drift| {
+                drift.as_ref().map_or(true, |(file, modified)| {
+                    (file.created - *modified).abs() > max_drift
+                })
             })
             .collect()
     }
-}
 
-impl Deref for Files {
-    type Target = Vec<File>;
+    /// Finds sets of files with identical content, e.g. photos copied into multiple folders.
+    ///
+    /// Files are compared by size and hash of their content first (see [`File::hash`]),
+    /// candidates with equal hash are then compared byte by byte.
+    ///
+    /// # Returns
+    ///
+    /// Sets of at least two identical files, each sorted by path, ordered by their first path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read.
+    pub fn duplicates(&self) -> Result<Vec<Vec<&File>>> {
+        let hashed = self
+            .files
+            .par_iter()
+            .map(|file| Ok((file.hash()?, file)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut buckets = HashMap::<(u64, u64), Vec<&File>>::new();
+        hashed
+            .into_iter()
+            .for_each(|(hash, file)| buckets.entry(hash).or_default().push(file));
+        let mut sets = Vec::new();
+        for mut bucket in buckets.into_values().filter(|bucket| bucket.len() > 1) {
+            bucket.sort_by(|a, b| ByPath(*a).cmp(&ByPath(*b)));
+            // Equal hashes of different content split the bucket into several sets
+            let mut identical = Vec::<(Vec<u8>, Vec<&File>)>::new();
+            for file in bucket {
+                let content = fs::read(&file.path)?;
+                match identical.iter_mut().find(|(other, _)| *other == content) {
+                    Some((_, set)) => set.push(file),
+                    None => identical.push((content, vec![file])),
+                }
+            }
+            sets.extend(identical.into_iter().map(|(_, set)| set));
+        }
+        sets.retain(|set| set.len() > 1);
+        sets.sort_by(|a, b| a[0].path.cmp(&b[0].path));
+        Ok(sets)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Finds the first and the last capture time of each day, see [`Files::group_by_days`].
+    ///
+    /// Days are returned in chronological order.
+    pub fn day_spans(&self) -> Vec<DaySpan> {
+        self.group_by_days()
+            .into_iter()
+            .filter_map(|group| {
+                let first = group.iter().map(|file| file.created).min()?;
+                let last = group.iter().map(|file| file.created).max()?;
+                Some(DaySpan {
+                    day: first.date(),
+                    first: first.time(),
+                    last: last.time(),
+                })
+            })
+            .collect()
     }
-}
-impl DerefMut for Files {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+
+    /// Counts files per calendar day of their creation date.
+    ///
+    /// Days without any files are not present in the returned map.
+    pub fn counts_by_day(&self) -> BTreeMap<NaiveDate, usize> {
+        self.group_by_days()
+            .into_iter()
+            .filter_map(|group| Some((group.first()?.created.date(), group.len())))
+            .collect()
     }
-}
 
-#[cfg(test)]
+    /// Counts files per calendar day from the first to the last day of [`Files::interval`].
+    ///
+    /// Unlike [`Files::counts_by_day`], days without any files are included with zero
+    /// count, so gaps between the days are visible. Returns no days if there are no files.
+    pub fn counts_by_day_in_span(&self) -> Vec<(NaiveDate, usize)> {
+        let counts = self.counts_by_day();
+        self.interval()
+            .map(|interval| {
+                interval
+                    .from
+                    .date()
+                    .iter_days()
+                    .take_while(|day| *day <= interval.to.date())
+                    .map(|day| (day, counts.get(&day).copied().unwrap_or_default()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Counts files per calendar month (formatted as `YYYY-MM`) of their creation date.
+    ///
+    /// Months without any files are not present in the returned map.
+    pub fn counts_by_month(&self) -> BTreeMap<String, usize> {
+        self.files.iter().fold(BTreeMap::new(), |mut counts, file| {
+            *counts
+                .entry(file.created.format("%Y-%m").to_string())
+                .or_default() += 1;
+            counts
+        })
+    }
+
+    /// Counts files per extension, lowercased so `IMG.JPG` and `pic.jpg` are counted
+    /// together. Files without an extension are counted under `(none)`.
+    pub fn counts_by_extension(&self) -> BTreeMap<String, usize> {
+        self.files.iter().fold(BTreeMap::new(), |mut counts, file| {
+            let extension = file
+                .path
+                .extension()
+                .map_or("(none)".to_string(), |e| e.to_string_lossy().to_lowercase());
+            *counts.entry(extension).or_default() += 1;
+            counts
+        })
+    }
+
+    /// Groups files by their creation date formatted with the given format strings
+    /// and generates new paths where each file would be moved to nested subdirectories,
+    /// one level per format string, within the same parent directory, or within
+    /// `root` if it is given.
+    ///
+    /// Files are sorted by creation date, so the formats must produce keys that
+    /// keep files of one group next to each other (e.g. `%Y-%m` or `%Y-%m-%d`).
+    ///
+    /// # Returns
+    ///
+    /// A vector of vectors, where each inner vector represents one group of files
+    /// and contains `RenamedFile` instances with original file references and new paths.
+    /// Files that cannot generate valid new paths (e.g., files without parent directories
+    /// or file names) are filtered out, see [`Files::unplanned`].
+    fn move_by_formats(&self, formats: &[&str], root: Option<&Path>) -> Vec<RenamedFiles<'_>> {
+        let key = |file: &File| {
+            formats
+                .iter()
+                .map(|format| file.created.format(format).to_string())
+                .collect::<Vec<_>>()
+        };
+        // Sorted by local time, as sorting by the UTC instant could put a file of another
+        // time zone between files with the same key and split their group in `chunk_by`
+        let files = self.get_sorted_by(|a, b| a.created.cmp(&b.created));
+        files
+            .chunk_by(|a, b| key(a) == key(b))
+            .map(|group| {
+                group
+                    .iter()
+                    .filter_map(|file| {
+                        root.or(file.path.parent())
+                            .map(|parent| {
+                                key(file)
+                                    .into_iter()
+                                    .fold(parent.to_path_buf(), |path, dir| path.join(dir))
+                            })
+                            .and_then(|path| Some(path.join(file.path.file_name()?)))
+                            .map(|new_path| RenamedFile(file, new_path))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Plans moving files into subdirectories named after their creation day,
+    /// see [`Files::move_by_formats`].
+    ///
+    /// # Examples
+    ///
+    /// For a file "/photos/IMG_001.jpg" created on 2025-05-01:
+    /// - New path would be "/photos/2025-05-01/IMG_001.jpg"
+    pub fn move_by_days(&self) -> Vec<RenamedFiles<'_>> {
+        self.move_by_formats(&["%Y-%m-%d"], None)
+    }
+
+    /// Plans moving files into subdirectories of `root` named after their creation day,
+    /// including files read from nested subdirectories, see [`Files::move_by_formats`].
+    /// The `root` may be the read directory or any other output directory.
+    ///
+    /// Files of one day with the same name, or with a name already taken in `root`,
+    /// are numbered, see [`number_collisions`].
+    ///
+    /// # Examples
+    ///
+    /// For files "/photos/a/IMG_001.jpg" and "/photos/b/IMG_001.jpg" created on 2025-05-01:
+    /// - New paths would be "/photos/2025-05-01/IMG_001.jpg" and "/photos/2025-05-01/IMG_001_1.jpg"
+    /// - With root "/sorted", new paths would be "/sorted/2025-05-01/IMG_001.jpg" and
+    ///   "/sorted/2025-05-01/IMG_001_1.jpg"
+    pub fn move_by_days_flatten(&self, root: &Path) -> Vec<RenamedFiles<'_>> {
+        self.move_by_formats(&["%Y-%m-%d"], Some(root))
+            .into_iter()
+            .map(number_collisions)
+            .collect()
+    }
+
+    /// Plans moving files into subdirectories named after their creation day and GPS
+    /// position rounded to `decimals` places, e.g. `2025-05-01 48.85,2.35`, so photos
+    /// from several places of one day are kept apart. Files without GPS position are
+    /// moved into subdirectories named after the day only, like [`Files::move_by_days`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be opened to read its position.
+    ///
+    /// # Examples
+    ///
+    /// For a file "/photos/IMG_001.jpg" created on 2025-05-01 at 48.8566 N, 2.3522 E:
+    /// - New path would be "/photos/2025-05-01 48.86,2.35/IMG_001.jpg"
+    pub fn move_by_location(&self, decimals: usize) -> io::Result<Vec<RenamedFiles<'_>>> {
+        let scale = 10f64.powi(decimals as i32);
+        // Adding zero turns negative zero into zero, so it is not written as `-0.00`
+        let round = |degrees: f64| (degrees * scale).round() / scale + 0.0;
+        let located = self
+            .get_sorted_by(|a, b| a.created.cmp(&b.created))
+            .into_par_iter()
+            .map(|file| Ok((file, file.location()?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut groups = BTreeMap::<String, RenamedFiles>::new();
+        for (file, location) in located {
+            let mut dir = file.created.format("%Y-%m-%d").to_string();
+            if let Some((latitude, longitude)) = location {
+                dir.push_str(&format!(
+                    " {:.decimals$},{:.decimals$}",
+                    round(latitude),
+                    round(longitude)
+                ));
+            }
+            if let Some((parent, name)) = file.path.parent().zip(file.path.file_name()) {
+                let new_path = parent.join(&dir).join(name);
+                groups
+                    .entry(dir)
+                    .or_default()
+                    .push(RenamedFile(file, new_path));
+            }
+        }
+        Ok(groups.into_values().collect())
+    }
+
+    /// Returns files left out of a move `plan`, e.g. files without a parent directory
+    /// or a file name, for which [`Files::move_by_days`] and others cannot plan a move.
+    pub fn unplanned<'a>(&'a self, plan: &[RenamedFiles]) -> Vec<&'a File> {
+        let planned = plan
+            .iter()
+            .flatten()
+            .map(|RenamedFile(file, _)| &file.path)
+            .collect::<HashSet<_>>();
+        self.files
+            .iter()
+            .filter(|file| !planned.contains(&file.path))
+            .collect()
+    }
+
+    /// Plans moving files into subdirectories named after their creation month,
+    /// see [`Files::move_by_formats`].
+    ///
+    /// # Examples
+    ///
+    /// For a file "/photos/IMG_001.jpg" created on 2025-05-01:
+    /// - New path would be "/photos/2025-05/IMG_001.jpg"
+    pub fn move_by_month(&self) -> Vec<RenamedFiles<'_>> {
+        self.move_by_formats(&["%Y-%m"], None)
+    }
+
+    /// Plans moving files into nested year, month and day subdirectories,
+    /// as deep as given by `levels`, see [`Files::move_by_formats`].
+    ///
+    /// # Examples
+    ///
+    /// For a file "/photos/IMG_001.jpg" created on 2025-05-01 and [`DateTree::YearMonthDay`]:
+    /// - New path would be "/photos/2025/05/01/IMG_001.jpg"
+    pub fn move_by_date_tree(&self, levels: DateTree) -> Vec<RenamedFiles<'_>> {
+        self.move_by_formats(levels.formats(), None)
+    }
+
+    /// Reads every planned destination of a move plan after the move, with the same
+    /// options the plan was made from.
+    ///
+    /// Every planned destination is expected to contain a file with the same
+    /// creation date as the original file.
+    ///
+    /// # Returns
+    ///
+    /// A list of discrepancies, empty if every planned file is in place.
+    pub fn verify_moves(plan: &[RenamedFile], options: &ReadOptions) -> Vec<MoveDiscrepancy> {
+        plan.par_iter()
+            .filter_map(
+                |RenamedFile(file, new_path)| match File::read(new_path.clone(), options) {
+                    Ok(Some(found)) if found.created != file.created => {
+                        Some(MoveDiscrepancy::DateMismatch {
+                            path: new_path.clone(),
+                            expected: file.created,
+                            found: found.created,
+                        })
+                    }
+                    Ok(Some(_)) => None,
+                    Ok(None) | Err(_) => Some(MoveDiscrepancy::Missing(new_path.clone())),
+                },
+            )
+            .collect()
+    }
+}
+
+impl Deref for Files {
+    type Target = Vec<File>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.files
+    }
+}
+impl DerefMut for Files {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.files
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use std::str::FromStr;
 
-    use chrono::NaiveDateTime;
-
-    use crate::file::ByPath;
+    use crate::{
+        file::{ByCreatedDate, DateSource},
+        test_utils::write_photo,
+    };
 
     use super::*;
 
@@ -247,14 +1334,20 @@ mod tests {
             File {
                 path: PathBuf::from("./1.jpg"),
                 created: NaiveDateTime::from_str("2025-05-01T12:13:14").unwrap(),
+                source: DateSource::Exif,
+                offset: None,
             },
             File {
                 path: PathBuf::from("./2.png"),
                 created: NaiveDateTime::from_str("2025-05-01T14:15:16").unwrap(),
+                source: DateSource::Exif,
+                offset: None,
             },
             File {
                 path: PathBuf::from("./3"),
                 created: NaiveDateTime::from_str("2025-05-03T12:13:14").unwrap(),
+                source: DateSource::Exif,
+                offset: None,
             },
         ]
     }
@@ -263,10 +1356,10 @@ mod tests {
     fn interval() {
         let [file1, file2, file3] = testing_files();
 
-        let files = Files(vec![]);
+        let files = Files::new(vec![]);
         assert_eq!(files.interval(), None);
 
-        let files = Files([&file1].into_iter().cloned().collect());
+        let files = Files::new([&file1].into_iter().cloned().collect());
         assert_eq!(
             files.interval(),
             Some(FilesInterval {
@@ -275,7 +1368,7 @@ mod tests {
             })
         );
 
-        let files = Files([&file1, &file2].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file2].into_iter().cloned().collect());
         assert_eq!(
             files.interval(),
             Some(FilesInterval {
@@ -284,7 +1377,7 @@ mod tests {
             })
         );
 
-        let files = Files([&file1, &file3].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file3].into_iter().cloned().collect());
         assert_eq!(
             files.interval(),
             Some(FilesInterval {
@@ -293,7 +1386,7 @@ mod tests {
             })
         );
 
-        let files = Files([&file1, &file3, &file2].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file3, &file2].into_iter().cloned().collect());
         assert_eq!(
             files.interval(),
             Some(FilesInterval {
@@ -303,43 +1396,113 @@ mod tests {
         );
     }
 
+    #[test]
+    fn interval_mixed_offsets() {
+        let file = |created: &str, offset: i32| File {
+            path: PathBuf::from("./1.jpg"),
+            created: NaiveDateTime::from_str(created).unwrap(),
+            source: DateSource::Exif,
+            offset: chrono::FixedOffset::east_opt(offset * 3600),
+        };
+        // The second file is taken earlier in UTC, but on the next local day
+        let files = Files::new(vec![
+            file("2025-05-01T23:00:00", 0),
+            file("2025-05-02T01:00:00", 5),
+        ]);
+        assert_eq!(
+            files.interval(),
+            Some(FilesInterval {
+                from: NaiveDateTime::from_str("2025-05-01T23:00:00").unwrap(),
+                to: NaiveDateTime::from_str("2025-05-02T01:00:00").unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn retain_after() {
+        let [file1, file2, file3] = testing_files();
+
+        let mut files = Files::new([&file1, &file2, &file3].into_iter().cloned().collect());
+        files.retain_after(file2.created);
+        assert_eq!(*files, vec![file3.clone()]);
+
+        let mut files = Files::new([&file1, &file2, &file3].into_iter().cloned().collect());
+        files.retain_after(NaiveDateTime::from_str("2025-01-01T00:00:00").unwrap());
+        assert_eq!(*files, vec![file1, file2, file3]);
+    }
+
+    #[test]
+    fn append() {
+        let [file1, file2, file3] = testing_files();
+        let mut files = Files::new(vec![file1.clone()]);
+        let mut other = Files::new(vec![file2.clone(), file3.clone()]);
+        other.skipped.push(PathBuf::from("./no-date.txt"));
+
+        files.append(other);
+        assert_eq!(*files, vec![file1, file2, file3]);
+        assert_eq!(files.skipped(), [PathBuf::from("./no-date.txt")]);
+    }
+
+    #[test]
+    fn retain_range() {
+        let [file1, file2, file3] = testing_files();
+        let day = |s: &str| NaiveDate::from_str(s).unwrap();
+        let retained = |from, to| {
+            let mut files = Files::new([&file1, &file2, &file3].into_iter().cloned().collect());
+            files.retain_range(from, to);
+            files.files
+        };
+
+        assert_eq!(
+            retained(Some(day("2025-05-01")), Some(day("2025-05-01"))),
+            [file1.clone(), file2.clone()]
+        );
+        assert_eq!(retained(Some(day("2025-05-02")), None), vec![file3.clone()]);
+        assert_eq!(
+            retained(None, Some(day("2025-05-02"))),
+            [file1.clone(), file2.clone()]
+        );
+        assert_eq!(retained(Some(day("2030-01-01")), None), []);
+        assert_eq!(retained(None, None), [file1, file2, file3]);
+    }
+
     #[test]
     fn rename_files() -> Result<()> {
         let [file1, file2, file3] = testing_files();
 
-        let files = Files(vec![]);
+        let files = Files::new(vec![]);
         assert_eq!(
-            files.rename_files::<ByPath<&File>>("new name", Some(4))?,
+            files.rename_files::<ByPath<&File>>("new name", Some(4), &NameTemplate::default())?,
             vec![]
         );
 
-        let files = Files([&file1].into_iter().cloned().collect());
+        let files = Files::new([&file1].into_iter().cloned().collect());
         assert_eq!(
-            files.rename_files::<ByPath<&File>>("new_name", Some(4))?,
+            files.rename_files::<ByPath<&File>>("new_name", Some(4), &NameTemplate::default())?,
             vec![RenamedFile(&file1, PathBuf::from("./new_name 0001.jpg"))]
         );
 
-        let files = Files([&file1, &file2].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file2].into_iter().cloned().collect());
         assert_eq!(
-            files.rename_files::<ByPath<&File>>("new_name", Some(4))?,
+            files.rename_files::<ByPath<&File>>("new_name", Some(4), &NameTemplate::default())?,
             vec![
                 RenamedFile(&file1, PathBuf::from("./new_name 0001.jpg")),
                 RenamedFile(&file2, PathBuf::from("./new_name 0002.png"))
             ]
         );
 
-        let files = Files([&file1, &file3].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file3].into_iter().cloned().collect());
         assert_eq!(
-            files.rename_files::<ByPath<&File>>("new_name", Some(4))?,
+            files.rename_files::<ByPath<&File>>("new_name", Some(4), &NameTemplate::default())?,
             vec![
                 RenamedFile(&file1, PathBuf::from("./new_name 0001.jpg")),
                 RenamedFile(&file3, PathBuf::from("./new_name 0002")),
             ]
         );
 
-        let files = Files([&file1, &file3, &file2].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file3, &file2].into_iter().cloned().collect());
         assert_eq!(
-            files.rename_files::<ByPath<&File>>("new_name", Some(4))?,
+            files.rename_files::<ByPath<&File>>("new_name", Some(4), &NameTemplate::default())?,
             vec![
                 RenamedFile(&file1, PathBuf::from("./new_name 0001.jpg")),
                 RenamedFile(&file2, PathBuf::from("./new_name 0002.png")),
@@ -351,49 +1514,1449 @@ mod tests {
     }
 
     #[test]
-    fn move_by_days() {
+    fn get_sorted_reverse() {
         let [file1, file2, file3] = testing_files();
+        let files = Files::new([&file3, &file1, &file2].into_iter().cloned().collect());
 
-        let files = Files(vec![]);
-        assert_eq!(files.move_by_days(), Vec::<RenamedFiles>::new());
+        let ascending = files.get_sorted::<ByPath<&File>>(false);
+        assert_eq!(ascending, [&file1, &file2, &file3]);
+        let mut descending = files.get_sorted::<ByPath<&File>>(true);
+        descending.reverse();
+        assert_eq!(descending, ascending);
+
+        let ascending = files.get_sorted::<ByCreatedDate<&File>>(false);
+        assert_eq!(ascending, [&file1, &file2, &file3]);
+        let mut descending = files.get_sorted::<ByCreatedDate<&File>>(true);
+        descending.reverse();
+        assert_eq!(descending, ascending);
+    }
+
+    #[test]
+    fn get_sorted_by() {
+        let [file1, file2, file3] = testing_files();
+        let files = Files::new([&file3, &file1, &file2].into_iter().cloned().collect());
 
-        let files = Files([&file1].into_iter().cloned().collect());
         assert_eq!(
-            files.move_by_days(),
-            vec![vec![RenamedFile(
-                &file1,
-                PathBuf::from("./2025-05-01/1.jpg")
-            )]]
+            files.get_sorted_by(|a, b| a.path.cmp(&b.path)),
+            vec![&file1, &file2, &file3]
         );
-
-        let files = Files([&file1, &file2].into_iter().cloned().collect());
         assert_eq!(
-            files.move_by_days(),
-            vec![vec![
-                RenamedFile(&file1, PathBuf::from("./2025-05-01/1.jpg")),
-                RenamedFile(&file2, PathBuf::from("./2025-05-01/2.png"))
-            ]]
+            files.get_sorted_by(|a, b| b.created.date().cmp(&a.created.date())),
+            vec![&file3, &file1, &file2]
         );
+    }
+
+    #[test]
+    fn rename_files_stable() -> Result<()> {
+        let [file1, file2, file3] = testing_files();
+        let mut index = StableIndex::default();
 
-        let files = Files([&file1, &file3].into_iter().cloned().collect());
+        let files = Files::new([&file1, &file3].into_iter().cloned().collect());
         assert_eq!(
-            files.move_by_days(),
+            files.rename_files_stable::<ByCreatedDate<&File>>(
+                "new_name",
+                Some(4),
+                &NameTemplate::default(),
+                &mut index,
+                false
+            )?,
             vec![
-                vec![RenamedFile(&file1, PathBuf::from("./2025-05-01/1.jpg"))],
-                vec![RenamedFile(&file3, PathBuf::from("./2025-05-03/3"))],
+                RenamedFile(&file1, PathBuf::from("./new_name 0001.jpg")),
+                RenamedFile(&file3, PathBuf::from("./new_name 0002")),
             ]
         );
 
-        let files = Files([&file1, &file3, &file2].into_iter().cloned().collect());
+        // A file added in the middle of the sequence does not shift the others.
+        let files = Files::new([&file1, &file2, &file3].into_iter().cloned().collect());
         assert_eq!(
-            files.move_by_days(),
+            files.rename_files_stable::<ByCreatedDate<&File>>(
+                "new_name",
+                Some(4),
+                &NameTemplate::default(),
+                &mut index,
+                false
+            )?,
             vec![
-                vec![
-                    RenamedFile(&file1, PathBuf::from("./2025-05-01/1.jpg")),
-                    RenamedFile(&file2, PathBuf::from("./2025-05-01/2.png"))
-                ],
-                vec![RenamedFile(&file3, PathBuf::from("./2025-05-03/3"))],
+                RenamedFile(&file1, PathBuf::from("./new_name 0001.jpg")),
+                RenamedFile(&file2, PathBuf::from("./new_name 0003.png")),
+                RenamedFile(&file3, PathBuf::from("./new_name 0002")),
+            ]
+        );
+        assert_eq!(index.get("2.png"), Some(3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rename_files_collisions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        let files = Files::new(
+            ["a.jpg", "b.jpg"]
+                .map(|name| File {
+                    path: path(name),
+                    created: NaiveDateTime::default(),
+                    source: DateSource::Exif,
+                    offset: None,
+                })
+                .to_vec(),
+        );
+
+        // Two files mapped to the same number
+        fs::write(path("index.txt"), "1\ta.jpg\n1\tb.jpg\n")?;
+        let mut index = StableIndex::read(path("index.txt"))?;
+        let err = files
+            .rename_files_stable::<ByPath<&File>>(
+                "p",
+                None,
+                &NameTemplate::default(),
+                &mut index,
+                false,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("would be renamed to"));
+
+        // Target taken by a file that is not renamed
+        fs::write(path("p 1.jpg"), "")?;
+        let err = files
+            .rename_files::<ByPath<&File>>("p", None, &NameTemplate::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("would overwrite existing file"));
+
+        // Target taken by a file that is renamed itself
+        let files = Files::new(
+            ["a.jpg", "p 1.jpg"]
+                .map(|name| File {
+                    path: path(name),
+                    created: NaiveDateTime::default(),
+                    source: DateSource::Exif,
+                    offset: None,
+                })
+                .to_vec(),
+        );
+        assert!(files
+            .rename_files::<ByPath<&File>>("p", None, &NameTemplate::default())
+            .is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn rename_files_lowercase_extensions() -> Result<()> {
+        let files = Files::new(
+            ["./IMG.JPG", "./pic.jpg", "./Raw.Cr2", "./NOEXT"]
+                .map(|name| File {
+                    path: PathBuf::from(name),
+                    created: NaiveDateTime::default(),
+                    source: DateSource::Exif,
+                    offset: None,
+                })
+                .to_vec(),
+        );
+        let new_names = |renamed: RenamedFiles| {
+            renamed
+                .into_iter()
+                .map(|RenamedFile(_, new_path)| new_path)
+                .collect::<Vec<_>>()
+        };
+
+        let renamed =
+            files.rename_files::<ByPath<&File>>("Trip.NAME", None, &NameTemplate::default())?;
+        assert_eq!(
+            new_names(lowercase_extensions(renamed)?),
+            [
+                "./Trip.NAME 1.jpg",
+                "./Trip.NAME 2",
+                "./Trip.NAME 3.cr2",
+                "./Trip.NAME 4.jpg",
             ]
+            .map(PathBuf::from)
+        );
+
+        // Extension casing is preserved by default
+        let renamed = files.rename_files::<ByPath<&File>>("p", None, &NameTemplate::default())?;
+        assert_eq!(
+            new_names(renamed),
+            ["./p 1.JPG", "./p 2", "./p 3.Cr2", "./p 4.jpg"].map(PathBuf::from)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn apply_renames() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        ["1.jpg", "2.jpg", "3.jpg"]
+            .iter()
+            .try_for_each(|name| fs::write(path(name), name))?;
+        let files = ["1.jpg", "2.jpg", "3.jpg"].map(|name| File {
+            path: path(name),
+            created: NaiveDateTime::default(),
+            source: DateSource::Exif,
+            offset: None,
+        });
+
+        let mut renames = 0;
+        Files::apply_renames(
+            &[
+                RenamedFile(&files[1], path("1.jpg")),
+                RenamedFile(&files[0], path("2.jpg")),
+                RenamedFile(&files[2], path("3.jpg")),
+            ],
+            |_, _| {
+                renames += 1;
+                Ok(())
+            },
+        )?;
+        assert_eq!(renames, 4);
+        assert_eq!(fs::read_to_string(path("1.jpg"))?, "2.jpg");
+        assert_eq!(fs::read_to_string(path("2.jpg"))?, "1.jpg");
+        assert_eq!(fs::read_to_string(path("3.jpg"))?, "3.jpg");
+        assert_eq!(fs::read_dir(dir.path())?.count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn apply_renames_failure() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 12:13:14");
+        let files = Files::read(dir.path(), &ReadOptions::default())?;
+        let plan = files
+            .move_by_days()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        let names = || -> Result<Vec<_>> {
+            let mut names = fs::read_dir(dir.path())?
+                .map(|entry| Ok(entry?.file_name()))
+                .collect::<io::Result<Vec<_>>>()?;
+            names.sort();
+            Ok(names)
+        };
+
+        // A file takes the name of a new directory
+        fs::write(dir.path().join("2025-05-03"), "")?;
+        let err = Files::apply_renames(&plan, |_, _| Ok(())).unwrap_err();
+        assert!(err.to_string().contains("is not a directory"));
+        assert_eq!(names()?, ["1.jpg", "2.jpg", "2025-05-03"]);
+
+        // Recording the third rename fails, after the first file is in its directory
+        fs::remove_file(dir.path().join("2025-05-03"))?;
+        let mut renames = Vec::new();
+        let result = Files::apply_renames(&plan, |from, to| {
+            renames.push((from.to_path_buf(), to.to_path_buf()));
+            match renames.len() {
+                3 => Err(anyhow!("Cannot record")),
+                _ => Ok(()),
+            }
+        });
+        assert!(result.is_err());
+        assert_eq!(names()?, ["1.jpg", "2.jpg"]);
+        // The moves back are recorded too
+        assert_eq!(renames.len(), 6);
+        assert_eq!(renames[3], (renames[2].1.clone(), renames[2].0.clone()));
+        Ok(())
+    }
+
+    #[test]
+    fn apply_renames_to_new_directories() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 12:13:14");
+        write_photo(dir.path().join("3.jpg"), "2025:05:04 12:13:14");
+        let files = Files::read(dir.path(), &ReadOptions::default())?;
+
+        // Leave the last file in place
+        let mut plan = files
+            .move_by_days()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        plan.retain(|RenamedFile(file, _)| !file.path.ends_with("3.jpg"));
+        Files::apply_renames(&plan, |_, _| Ok(()))?;
+
+        assert!(dir.path().join("2025-05-01/1.jpg").is_file());
+        assert!(dir.path().join("2025-05-03/2.jpg").is_file());
+        assert!(dir.path().join("3.jpg").is_file());
+        assert!(!dir.path().join("2025-05-04").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn order_breaks() -> Result<()> {
+        let files = Files::new(
+            (1..=10)
+                .map(|i| File {
+                    path: PathBuf::from(format!("./{i:02}.jpg")),
+                    created: NaiveDateTime::from_str(&format!("2025-05-01T12:00:{i:02}")).unwrap(),
+                    source: DateSource::Exif,
+                    offset: None,
+                })
+                .collect(),
+        );
+
+        let renamed = files.rename_files::<ByPath<&File>>("p", None, &NameTemplate::default())?;
+        assert_eq!(super::order_breaks(&renamed), vec![]);
+
+        // Numbers without padding, e.g. from a custom plan
+        let renamed = files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| RenamedFile(file, PathBuf::from(format!("./p {}.jpg", i + 1))))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            super::order_breaks(&renamed),
+            vec![(&renamed[9], &renamed[1])]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn clock_jumps() {
+        let [file1, file2, file3] = testing_files();
+
+        let files = Files::new([&file1, &file2, &file3].into_iter().cloned().collect());
+        assert_eq!(files.clock_jumps(), vec![]);
+
+        let reset = File {
+            path: PathBuf::from("./2a.jpg"),
+            created: NaiveDateTime::from_str("2000-01-01T00:00:00").unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+        let files = Files::new(
+            [&file3, &reset, &file1, &file2]
+                .into_iter()
+                .cloned()
+                .collect(),
+        );
+        assert_eq!(files.clock_jumps(), vec![(&file2, &reset)]);
+    }
+
+    #[test]
+    fn counts_by_day() {
+        let [file1, file2, file3] = testing_files();
+
+        let files = Files::new(vec![]);
+        assert_eq!(files.counts_by_day(), BTreeMap::new());
+
+        let files = Files::new([&file1, &file3, &file2].into_iter().cloned().collect());
+        assert_eq!(
+            files.counts_by_day(),
+            BTreeMap::from([
+                (NaiveDate::from_ymd_opt(2025, 5, 1).unwrap(), 2),
+                (NaiveDate::from_ymd_opt(2025, 5, 3).unwrap(), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn counts_by_day_in_span() {
+        let [file1, file2, file3] = testing_files();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 5, d).unwrap();
+
+        assert_eq!(Files::new(vec![]).counts_by_day_in_span(), vec![]);
+
+        let files = Files::new([&file1, &file3, &file2].into_iter().cloned().collect());
+        assert_eq!(
+            files.counts_by_day_in_span(),
+            vec![(day(1), 2), (day(2), 0), (day(3), 1)]
+        );
+
+        let files = Files::new(vec![file1]);
+        assert_eq!(files.counts_by_day_in_span(), vec![(day(1), 1)]);
+    }
+
+    #[test]
+    fn counts_by_month() {
+        let [file1, file2, file3] = testing_files();
+        let file4 = File {
+            path: PathBuf::from("./4.jpg"),
+            created: NaiveDateTime::from_str("2025-06-02T10:00:00").unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+
+        let files = Files::new(vec![]);
+        assert_eq!(files.counts_by_month(), BTreeMap::new());
+
+        let files = Files::new(vec![file4, file3, file1, file2]);
+        assert_eq!(
+            files.counts_by_month(),
+            BTreeMap::from([("2025-05".to_string(), 3), ("2025-06".to_string(), 1)])
         );
     }
+
+    #[test]
+    fn counts_by_extension() {
+        let file = |path: &str| File {
+            path: PathBuf::from(path),
+            created: NaiveDateTime::from_str("2025-05-01T10:00:00").unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+
+        let files = Files::new(vec![]);
+        assert_eq!(files.counts_by_extension(), BTreeMap::new());
+
+        let files = Files::new(vec![
+            file("./a.jpg"),
+            file("./b.JPG"),
+            file("./c.Cr2"),
+            file("./d"),
+            file("./e.tar.png"),
+        ]);
+        assert_eq!(
+            files.counts_by_extension(),
+            BTreeMap::from([
+                ("(none)".to_string(), 1),
+                ("cr2".to_string(), 1),
+                ("jpg".to_string(), 2),
+                ("png".to_string(), 1),
+            ])
+        );
+    }
+
+    #[test]
+    fn move_by_days_flatten() {
+        let file = |path: &str, created: &str| File {
+            path: PathBuf::from(path),
+            created: NaiveDateTime::from_str(created).unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+        let files = [
+            file("/photos/a/IMG_001.jpg", "2025-05-01T10:00:00"),
+            file("/photos/b/IMG_001.jpg", "2025-05-01T11:00:00"),
+            file("/photos/b/c/IMG_001.jpg", "2025-05-01T12:00:00"),
+            file("/photos/2025-05-02/IMG_002", "2025-05-02T10:00:00"),
+            file("/photos/a/IMG_002", "2025-05-02T11:00:00"),
+        ];
+        let [a, b, c, in_place, d] = &files;
+        let files = Files::new(files.to_vec());
+
+        assert_eq!(
+            files.move_by_days_flatten(Path::new("/photos")),
+            vec![
+                vec![
+                    RenamedFile(a, PathBuf::from("/photos/2025-05-01/IMG_001.jpg")),
+                    RenamedFile(b, PathBuf::from("/photos/2025-05-01/IMG_001_1.jpg")),
+                    RenamedFile(c, PathBuf::from("/photos/2025-05-01/IMG_001_2.jpg")),
+                ],
+                vec![
+                    RenamedFile(in_place, PathBuf::from("/photos/2025-05-02/IMG_002")),
+                    RenamedFile(d, PathBuf::from("/photos/2025-05-02/IMG_002_1")),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn move_by_days_out_root() {
+        let file = |path: &str, created: &str| File {
+            path: PathBuf::from(path),
+            created: NaiveDateTime::from_str(created).unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+        let files = [
+            file("/photos/a/IMG_001.jpg", "2025-05-01T10:00:00"),
+            file("/camera/IMG_001.jpg", "2025-05-01T11:00:00"),
+            file("/camera/IMG_002.jpg", "2025-05-02T10:00:00"),
+        ];
+        let [a, b, c] = &files;
+        let files = Files::new(files.to_vec());
+
+        assert_eq!(
+            files.move_by_days_flatten(Path::new("/sorted")),
+            vec![
+                vec![
+                    RenamedFile(a, PathBuf::from("/sorted/2025-05-01/IMG_001.jpg")),
+                    RenamedFile(b, PathBuf::from("/sorted/2025-05-01/IMG_001_1.jpg")),
+                ],
+                vec![RenamedFile(
+                    c,
+                    PathBuf::from("/sorted/2025-05-02/IMG_002.jpg")
+                )],
+            ]
+        );
+    }
+
+    #[test]
+    fn move_by_location() -> Result<()> {
+        use crate::test_utils::write_photo_at;
+
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        write_photo_at(path("a.jpg"), "2025:05:01 10:00:00", 48.8566, 2.3522);
+        write_photo_at(path("b.jpg"), "2025:05:01 11:00:00", 45.764, 4.8357);
+        write_photo_at(path("c.jpg"), "2025:05:01 12:00:00", 48.8584, 2.2945);
+        write_photo_at(path("d.jpg"), "2025:05:01 13:00:00", -0.001, -0.001);
+        write_photo(path("e.jpg"), "2025:05:01 14:00:00");
+        let files = Files::read(dir.path(), &ReadOptions::default())?;
+        let plan = |decimals| -> Result<Vec<Vec<(String, PathBuf)>>> {
+            Ok(files
+                .move_by_location(decimals)?
+                .into_iter()
+                .map(|group| {
+                    group
+                        .into_iter()
+                        .map(|RenamedFile(file, to)| {
+                            let name = file.path.file_name().unwrap().to_str().unwrap();
+                            (
+                                name.to_string(),
+                                to.strip_prefix(dir.path()).unwrap().into(),
+                            )
+                        })
+                        .collect()
+                })
+                .collect())
+        };
+        let moved = |name: &str, to: &str| (name.to_string(), PathBuf::from(to));
+
+        assert_eq!(
+            plan(2)?,
+            vec![
+                vec![moved("e.jpg", "2025-05-01/e.jpg")],
+                vec![moved("d.jpg", "2025-05-01 0.00,0.00/d.jpg")],
+                vec![moved("b.jpg", "2025-05-01 45.76,4.84/b.jpg")],
+                vec![moved("c.jpg", "2025-05-01 48.86,2.29/c.jpg")],
+                vec![moved("a.jpg", "2025-05-01 48.86,2.35/a.jpg")],
+            ]
+        );
+        assert_eq!(
+            plan(0)?[3],
+            vec![
+                moved("a.jpg", "2025-05-01 49,2/a.jpg"),
+                moved("c.jpg", "2025-05-01 49,2/c.jpg"),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days() {
+        let [file1, file2, file3] = testing_files();
+
+        let files = Files::new(vec![]);
+        assert_eq!(files.move_by_days(), Vec::<RenamedFiles>::new());
+
+        let files = Files::new([&file1].into_iter().cloned().collect());
+        assert_eq!(
+            files.move_by_days(),
+            vec![vec![RenamedFile(
+                &file1,
+                PathBuf::from("./2025-05-01/1.jpg")
+            )]]
+        );
+
+        let files = Files::new([&file1, &file2].into_iter().cloned().collect());
+        assert_eq!(
+            files.move_by_days(),
+            vec![vec![
+                RenamedFile(&file1, PathBuf::from("./2025-05-01/1.jpg")),
+                RenamedFile(&file2, PathBuf::from("./2025-05-01/2.png"))
+            ]]
+        );
+
+        let files = Files::new([&file1, &file3].into_iter().cloned().collect());
+        assert_eq!(
+            files.move_by_days(),
+            vec![
+                vec![RenamedFile(&file1, PathBuf::from("./2025-05-01/1.jpg"))],
+                vec![RenamedFile(&file3, PathBuf::from("./2025-05-03/3"))],
+            ]
+        );
+
+        let files = Files::new([&file1, &file3, &file2].into_iter().cloned().collect());
+        assert_eq!(
+            files.move_by_days(),
+            vec![
+                vec![
+                    RenamedFile(&file1, PathBuf::from("./2025-05-01/1.jpg")),
+                    RenamedFile(&file2, PathBuf::from("./2025-05-01/2.png"))
+                ],
+                vec![RenamedFile(&file3, PathBuf::from("./2025-05-03/3"))],
+            ]
+        );
+    }
+
+    #[test]
+    fn unplanned() {
+        let [file1, ..] = testing_files();
+        let parentless = File {
+            path: PathBuf::from("/"),
+            ..file1.clone()
+        };
+        let nameless = File {
+            path: PathBuf::from("./.."),
+            ..file1.clone()
+        };
+
+        let files = Files::new(vec![file1.clone(), parentless.clone(), nameless.clone()]);
+        let plan = files.move_by_days();
+        assert_eq!(
+            plan,
+            vec![vec![RenamedFile(
+                &file1,
+                PathBuf::from("./2025-05-01/1.jpg")
+            )]]
+        );
+        assert_eq!(files.unplanned(&plan), vec![&parentless, &nameless]);
+        // A root does not help files without a name
+        assert_eq!(
+            files.unplanned(&files.move_by_days_flatten(Path::new("/out"))),
+            vec![&parentless, &nameless]
+        );
+    }
+
+    #[test]
+    fn move_by_month() {
+        let [file1, file2, file3] = testing_files();
+        let file4 = File {
+            path: PathBuf::from("./sub/4.jpg"),
+            created: NaiveDateTime::from_str("2025-06-02T10:00:00").unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+
+        let files = Files::new(vec![]);
+        assert_eq!(files.move_by_month(), Vec::<RenamedFiles>::new());
+
+        let files = Files::new(vec![
+            file4.clone(),
+            file3.clone(),
+            file1.clone(),
+            file2.clone(),
+        ]);
+        assert_eq!(
+            files.move_by_month(),
+            vec![
+                vec![
+                    RenamedFile(&file1, PathBuf::from("./2025-05/1.jpg")),
+                    RenamedFile(&file2, PathBuf::from("./2025-05/2.png")),
+                    RenamedFile(&file3, PathBuf::from("./2025-05/3")),
+                ],
+                vec![RenamedFile(&file4, PathBuf::from("./sub/2025-06/4.jpg"))],
+            ]
+        );
+    }
+
+    #[test]
+    fn group_by_parent() {
+        let files = Files::new(
+            ["b/3.jpg", "a/1.jpg", "b/2.jpg", "4.jpg"]
+                .iter()
+                .map(|path| File {
+                    path: PathBuf::from(path),
+                    created: NaiveDateTime::from_str("2025-05-01T10:00:00").unwrap(),
+                    source: DateSource::Exif,
+                    offset: None,
+                })
+                .collect(),
+        );
+        let groups = files
+            .group_by_parent()
+            .into_iter()
+            .map(|(parent, files)| (parent, files.iter().map(|f| f.path.clone()).collect()))
+            .collect::<Vec<(PathBuf, Vec<PathBuf>)>>();
+        assert_eq!(
+            groups,
+            [
+                ("".into(), vec!["4.jpg".into()]),
+                ("a".into(), vec!["a/1.jpg".into()]),
+                ("b".into(), vec!["b/3.jpg".into(), "b/2.jpg".into()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn number_width() {
+        assert_eq!(super::number_width(0), 1);
+        assert_eq!(super::number_width(9), 1);
+        assert_eq!(super::number_width(10), 2);
+        assert_eq!(super::number_width(99), 2);
+        assert_eq!(super::number_width(100), 3);
+        assert_eq!(super::number_width(999), 3);
+        assert_eq!(super::number_width(1000), 4);
+    }
+
+    #[test]
+    fn rename_files_digits() -> Result<()> {
+        let files = |count: usize| {
+            Files::new(
+                (1..=count)
+                    .map(|i| File {
+                        path: PathBuf::from(format!("{i:05}.jpg")),
+                        created: NaiveDateTime::default(),
+                        source: DateSource::Exif,
+                        offset: None,
+                    })
+                    .collect(),
+            )
+        };
+        let names = |count: usize, digits| -> Result<(PathBuf, PathBuf)> {
+            let files = files(count);
+            let renamed =
+                files.rename_files::<ByPath<&File>>("p", digits, &NameTemplate::default())?;
+            Ok((renamed[0].1.clone(), renamed[count - 1].1.clone()))
+        };
+
+        assert_eq!(names(9, None)?, ("p 1.jpg".into(), "p 9.jpg".into()));
+        assert_eq!(names(10, None)?, ("p 01.jpg".into(), "p 10.jpg".into()));
+        assert_eq!(names(100, None)?, ("p 001.jpg".into(), "p 100.jpg".into()));
+        assert_eq!(
+            names(1000, None)?,
+            ("p 0001.jpg".into(), "p 1000.jpg".into())
+        );
+        // Explicit digits pad more, but are never fewer than the highest number needs
+        assert_eq!(names(9, Some(3))?, ("p 001.jpg".into(), "p 009.jpg".into()));
+        assert_eq!(names(10, Some(2))?, ("p 01.jpg".into(), "p 10.jpg".into()));
+        assert_eq!(
+            names(10, Some(1)).unwrap_err().to_string(),
+            "1 digits are not enough for number 10, at least 2 are needed"
+        );
+        assert!(names(150, Some(2)).is_err());
+
+        // Numbers continuing an index widen the automatic padding
+        let files = files(5);
+        let mut index = StableIndex::default().with_start(98);
+        let renamed = files.rename_files_stable::<ByPath<&File>>(
+            "p",
+            None,
+            &NameTemplate::default(),
+            &mut index,
+            false,
+        )?;
+        assert_eq!(renamed[0].1, PathBuf::from("p 098.jpg"));
+        assert_eq!(renamed[4].1, PathBuf::from("p 102.jpg"));
+        Ok(())
+    }
+
+    #[test]
+    fn group_by() {
+        let files = Files::new(
+            [
+                ("c.jpg", "2025-06-02T10:00:00"),
+                ("a.png", "2025-05-01T10:00:00"),
+                ("b.jpg", "2025-05-03T10:00:00"),
+                ("d", "2025-06-01T10:00:00"),
+            ]
+            .iter()
+            .map(|(path, created)| File {
+                path: PathBuf::from(path),
+                created: NaiveDateTime::from_str(created).unwrap(),
+                source: DateSource::Exif,
+                offset: None,
+            })
+            .collect(),
+        );
+        fn paths<K>(groups: Vec<(K, Vec<&File>)>) -> Vec<(K, Vec<PathBuf>)> {
+            groups
+                .into_iter()
+                .map(|(key, files)| (key, files.iter().map(|f| f.path.clone()).collect()))
+                .collect()
+        }
+
+        assert_eq!(
+            paths(files.group_by(|file| file.path.extension().map(|e| e.to_owned()))),
+            [
+                (None, vec!["d".into()]),
+                (Some("jpg".into()), vec!["c.jpg".into(), "b.jpg".into()]),
+                (Some("png".into()), vec!["a.png".into()]),
+            ]
+        );
+        assert_eq!(
+            paths(files.group_by(|file| file.created.format("%Y-%m").to_string())),
+            [
+                ("2025-05".to_string(), vec!["a.png".into(), "b.jpg".into()]),
+                ("2025-06".to_string(), vec!["c.jpg".into(), "d".into()]),
+            ]
+        );
+        assert!(Files::new(Vec::new())
+            .group_by(|file| file.created)
+            .is_empty());
+    }
+
+    #[test]
+    fn group_by_days() {
+        let files = |created: &[&str]| {
+            Files::new(
+                created
+                    .iter()
+                    .map(|created| File {
+                        path: PathBuf::new(),
+                        created: NaiveDateTime::from_str(created).unwrap(),
+                        source: DateSource::Exif,
+                        offset: None,
+                    })
+                    .collect(),
+            )
+        };
+        let group_sizes = |files: &Files| {
+            files
+                .group_by_days()
+                .iter()
+                .map(Vec::len)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(group_sizes(&files(&[])), Vec::<usize>::new());
+        assert_eq!(group_sizes(&files(&["2025-05-01T10:00:00"])), [1]);
+
+        // Single day with duplicate dates
+        let single_day = files(&[
+            "2025-05-01T10:00:00",
+            "2025-05-01T10:00:00",
+            "2025-05-01T23:59:59",
+            "2025-05-01T00:00:00",
+        ]);
+        assert_eq!(group_sizes(&single_day), [4]);
+
+        // Multiple days with duplicates in unsorted order
+        let multi_day = files(&[
+            "2025-05-03T08:00:00",
+            "2025-05-01T10:00:00",
+            "2025-05-03T08:00:00",
+            "2025-05-02T00:00:00",
+            "2025-05-01T10:00:00",
+            "2025-05-01T23:59:59",
+        ]);
+        let groups = multi_day.group_by_days();
+        assert_eq!(group_sizes(&multi_day), [3, 1, 2]);
+        assert!(groups.iter().all(|group| {
+            group
+                .iter()
+                .all(|file| file.created.date() == group[0].created.date())
+        }));
+    }
+
+    #[test]
+    fn drifted() -> Result<()> {
+        use chrono::{Local, TimeZone};
+
+        let dir = tempfile::tempdir()?;
+        let set_modified = |name: &str, modified: &str| -> Result<()> {
+            let modified = NaiveDateTime::from_str(modified)?;
+            fs::File::options()
+                .write(true)
+                .open(dir.path().join(name))?
+                .set_modified(Local.from_local_datetime(&modified).unwrap().into())?;
+            Ok(())
+        };
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:00:00");
+        write_photo(dir.path().join("2.jpg"), "2025:05:01 12:00:00");
+        write_photo(dir.path().join("3.jpg"), "2025:05:01 12:00:00");
+        set_modified("1.jpg", "2025-05-03T12:00:00")?;
+        set_modified("2.jpg", "2025-05-10T12:00:00")?;
+        set_modified("3.jpg", "2024-01-01T00:00:00")?;
+        let files = Files::read(dir.path(), &ReadOptions::default())?;
+
+        let drifted = files
+            .drifted(TimeDelta::days(7))?
+            .into_iter()
+            .map(|(file, modified)| (file.path.file_name().unwrap().to_owned(), modified))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            drifted,
+            [
+                (
+                    "2.jpg".into(),
+                    NaiveDateTime::from_str("2025-05-10T12:00:00")?
+                ),
+                (
+                    "3.jpg".into(),
+                    NaiveDateTime::from_str("2024-01-01T00:00:00")?
+                ),
+            ]
+        );
+        assert_eq!(files.drifted(TimeDelta::days(9))?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn day_spans() {
+        let files = Files::new(
+            [
+                "2025-05-02T09:15:00",
+                "2025-05-01T14:30:20",
+                "2025-05-01T10:00:00",
+                "2025-05-02T18:45:30",
+                "2025-05-01T12:00:00",
+                "2025-05-03T08:00:00",
+            ]
+            .map(|created| File {
+                path: PathBuf::new(),
+                created: NaiveDateTime::from_str(created).unwrap(),
+                source: DateSource::Exif,
+                offset: None,
+            })
+            .to_vec(),
+        );
+        let time = |s| NaiveTime::from_str(s).unwrap();
+        let spans = files.day_spans();
+        assert_eq!(
+            spans,
+            [
+                DaySpan {
+                    day: NaiveDate::from_ymd_opt(2025, 5, 1).unwrap(),
+                    first: time("10:00:00"),
+                    last: time("14:30:20"),
+                },
+                DaySpan {
+                    day: NaiveDate::from_ymd_opt(2025, 5, 2).unwrap(),
+                    first: time("09:15:00"),
+                    last: time("18:45:30"),
+                },
+                DaySpan {
+                    day: NaiveDate::from_ymd_opt(2025, 5, 3).unwrap(),
+                    first: time("08:00:00"),
+                    last: time("08:00:00"),
+                },
+            ]
+        );
+        assert_eq!(
+            spans.iter().map(DaySpan::duration).collect::<Vec<_>>(),
+            [
+                TimeDelta::seconds(4 * 3600 + 30 * 60 + 20),
+                TimeDelta::seconds(9 * 3600 + 30 * 60 + 30),
+                TimeDelta::zero(),
+            ]
+        );
+        assert_eq!(Files::new(Vec::new()).day_spans(), []);
+    }
+
+    #[test]
+    fn dedupe_content() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        fs::create_dir(path("a"))?;
+        fs::create_dir(path("b"))?;
+        let files = [
+            ("a/1.jpg", "same", "2025-05-01T10:00:00"),
+            ("b/1.jpg", "same", "2025-05-01T11:00:00"),
+            ("b/2.jpg", "other", "2025-05-01T12:00:00"),
+            ("a/2.jpg", "same", "2025-05-02T10:00:00"),
+        ]
+        .map(|(name, content, created)| {
+            fs::write(path(name), content).unwrap();
+            File {
+                path: path(name),
+                created: NaiveDateTime::from_str(created).unwrap(),
+                source: DateSource::Exif,
+                offset: None,
+            }
+        });
+        let [first, duplicate, other, next_day] = &files;
+        let files = Files::new(files.to_vec());
+
+        let (plan, duplicates) = super::dedupe_content(files.move_by_days())?;
+        assert_eq!(duplicates, [(duplicate, first)]);
+        assert_eq!(
+            plan.iter()
+                .map(|group| group.iter().map(|r| r.0).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            // Identical files from different days are kept
+            vec![vec![first, other], vec![next_day]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn duplicates() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let created = NaiveDateTime::from_str("2025-05-01T10:00:00")?;
+        let files = [
+            ("c.jpg", "same"),
+            ("a.jpg", "same"),
+            ("b.jpg", "other"),
+            ("d.jpg", "same"),
+            ("e.jpg", "other"),
+            ("f.jpg", "unique"),
+        ]
+        .map(|(name, content)| {
+            fs::write(dir.path().join(name), content).unwrap();
+            File {
+                path: dir.path().join(name),
+                created,
+                source: DateSource::Exif,
+                offset: None,
+            }
+        });
+        let [c, a, b, d, e, _] = &files;
+        let files = Files::new(files.to_vec());
+
+        assert_eq!(files.duplicates()?, vec![vec![a, c, d], vec![b, e]]);
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_local_day() {
+        let file = |name: &str, created: &str, offset: i32| File {
+            path: PathBuf::from(name),
+            created: NaiveDateTime::from_str(created).unwrap(),
+            source: DateSource::Exif,
+            offset: chrono::FixedOffset::east_opt(offset * 3600),
+        };
+        // Taken in between the other two in UTC, but on the next local day
+        let files = Files::new(vec![
+            file("./1.jpg", "2025-05-01T20:00:00", 0),
+            file("./2.jpg", "2025-05-02T01:00:00", 4),
+            file("./3.jpg", "2025-05-01T23:00:00", 0),
+        ]);
+        assert_eq!(
+            files
+                .move_by_days()
+                .into_iter()
+                .map(|group| group.into_iter().map(|r| r.1).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![
+                    PathBuf::from("./2025-05-01/1.jpg"),
+                    PathBuf::from("./2025-05-01/3.jpg")
+                ],
+                vec![PathBuf::from("./2025-05-02/2.jpg")],
+            ]
+        );
+        assert_eq!(files.group_by_days().len(), 2);
+    }
+
+    #[test]
+    fn move_by_date_tree() {
+        let [file1, file2, file3] = testing_files();
+        let files = Files::new(vec![file1.clone(), file3.clone(), file2.clone()]);
+        let new_paths = |levels| {
+            files
+                .move_by_date_tree(levels)
+                .into_iter()
+                .map(|group| group.into_iter().map(|r| r.1).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            new_paths(DateTree::Year),
+            [["./2025/1.jpg", "./2025/2.png", "./2025/3"].map(PathBuf::from)]
+        );
+        assert_eq!(
+            new_paths(DateTree::YearMonth),
+            [["./2025/05/1.jpg", "./2025/05/2.png", "./2025/05/3"].map(PathBuf::from)]
+        );
+        assert_eq!(
+            new_paths(DateTree::YearMonthDay),
+            vec![
+                vec![
+                    PathBuf::from("./2025/05/01/1.jpg"),
+                    PathBuf::from("./2025/05/01/2.png")
+                ],
+                vec![PathBuf::from("./2025/05/03/3")],
+            ]
+        );
+    }
+
+    #[test]
+    fn read_max_depth() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("sub/2.jpg"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join("sub/deeper/3.jpg"), "2025:05:03 12:13:14");
+        let read = |max_depth| -> Result<usize> {
+            let options = ReadOptions {
+                max_depth,
+                ..Default::default()
+            };
+            Ok(Files::read(dir.path(), &options)?.len())
+        };
+
+        assert_eq!(read(Some(1))?, 1);
+        assert_eq!(read(Some(2))?, 2);
+        assert_eq!(read(Some(3))?, 3);
+        assert_eq!(read(None)?, 3);
+        assert_eq!(Files::read(dir.path(), &ReadOptions::default())?.len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn read_parallel() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        (1..=20).for_each(|i| {
+            write_photo(
+                dir.path().join(format!("sub{}/{i}.jpg", i % 3)),
+                &format!("2025:05:01 12:00:{i:02}"),
+            )
+        });
+        let read = |jobs| {
+            let options = ReadOptions {
+                jobs,
+                ..Default::default()
+            };
+            Files::read(dir.path(), &options)
+        };
+
+        let sequential = read(Some(1))?;
+        assert_eq!(sequential.len(), 20);
+        assert_eq!(read(Some(4))?.files, sequential.files);
+        assert_eq!(read(None)?.files, sequential.files);
+        Ok(())
+    }
+
+    #[test]
+    fn plan_summary() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        fs::write(path("taken.jpg"), "")?;
+        let files = ["a.jpg", "b.jpg", "c.jpg", "d.jpg", "e.jpg"].map(|name| File {
+            path: path(name),
+            created: NaiveDateTime::default(),
+            source: DateSource::Exif,
+            offset: None,
+        });
+        let plan = vec![
+            RenamedFile(&files[0], path("1.jpg")),
+            RenamedFile(&files[1], path("b.jpg")),
+            RenamedFile(&files[2], path("taken.jpg")),
+            RenamedFile(&files[3], path("same.jpg")),
+            RenamedFile(&files[4], path("same.jpg")),
+        ];
+
+        assert_eq!(
+            Outcome::classify(&plan),
+            [
+                Outcome::Renamed,
+                Outcome::Unchanged,
+                Outcome::Conflict,
+                Outcome::Conflict,
+                Outcome::Conflict
+            ]
+        );
+        assert_eq!(
+            PlanSummary::new(&plan),
+            PlanSummary {
+                renamed: 1,
+                unchanged: 1,
+                conflicts: 3
+            }
+        );
+        assert_eq!(
+            PlanSummary::new(&plan).to_string(),
+            "1 to rename, 1 unchanged, 3 in conflict"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn plan_count() {
+        let files = ["a.jpg", "b.jpg", "c.jpg", "d.jpg"].map(|name| File {
+            path: PathBuf::from(name),
+            created: NaiveDateTime::default(),
+            source: DateSource::Exif,
+            offset: None,
+        });
+        let plan = vec![
+            RenamedFile(&files[0], PathBuf::from("1/a.jpg")),
+            RenamedFile(&files[1], PathBuf::from("1/b.jpg")),
+            RenamedFile(&files[2], PathBuf::from("2/c.jpg")),
+            RenamedFile(&files[3], PathBuf::from("d.jpg")),
+        ];
+
+        let count = PlanCount::new(&plan);
+        assert_eq!(
+            count,
+            PlanCount {
+                files: 3,
+                directories: 2,
+                skipped: 1
+            }
+        );
+        assert_eq!(
+            count.describe(("move", "Moved"), true),
+            "Would move 3 files into 2 directories (1 skipped)"
+        );
+        assert_eq!(
+            PlanCount::new(&plan[..1]).describe(("move", "Moved"), false),
+            "Moved 1 file into 1 directory (0 skipped)"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_unreadable_directory() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("sub/1.jpg"), "2025:05:01 12:13:14");
+        let sub = dir.path().join("sub");
+        fs::set_permissions(&sub, fs::Permissions::from_mode(0o000))?;
+        // Privileged users read the directory regardless of its permissions
+        let readable = fs::read_dir(&sub).is_ok();
+        let read = Files::read(dir.path(), &ReadOptions::default());
+        fs::set_permissions(&sub, fs::Permissions::from_mode(0o755))?;
+
+        if !readable {
+            assert!(matches!(read, Err(PhotoDaterError::Io(_))));
+        }
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_symlink_cycle() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("sub/2.jpg"), "2025:05:02 12:13:14");
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("sub/loop"))?;
+        let options = ReadOptions {
+            follow_symlinks: true,
+            ..Default::default()
+        };
+
+        let files = Files::read(dir.path(), &options)?;
+        assert_eq!(files.len(), 2);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_symlinks() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let target = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(target.path().join("2.jpg"), "2025:05:02 12:13:14");
+        write_photo(target.path().join("sub/3.jpg"), "2025:05:03 12:13:14");
+        std::os::unix::fs::symlink(target.path().join("2.jpg"), dir.path().join("2.jpg"))?;
+        std::os::unix::fs::symlink(target.path().join("sub"), dir.path().join("sub"))?;
+        std::os::unix::fs::symlink(dir.path().join("missing"), dir.path().join("broken"))?;
+        let read = |follow_symlinks| -> Result<Vec<PathBuf>> {
+            let options = ReadOptions {
+                follow_symlinks,
+                ..Default::default()
+            };
+            let mut paths = Files::read(dir.path(), &options)?
+                .iter()
+                .map(|file| file.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+                .collect::<Vec<_>>();
+            paths.sort();
+            Ok(paths)
+        };
+
+        assert_eq!(read(false)?, [PathBuf::from("1.jpg")]);
+        assert_eq!(
+            read(true)?,
+            ["1.jpg", "2.jpg", "sub/3.jpg"].map(PathBuf::from)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_dot_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join(".hidden.jpg"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join(".cache/3.jpg"), "2025:05:03 12:13:14");
+        write_photo(dir.path().join("sub/.4.jpg"), "2025:05:04 12:13:14");
+        fs::write(dir.path().join(".DS_Store"), "")?;
+        let read = |dot_files| -> Result<(Vec<PathBuf>, usize)> {
+            let options = ReadOptions {
+                dot_files,
+                ..Default::default()
+            };
+            let files = Files::read(dir.path(), &options)?;
+            let mut paths = files
+                .iter()
+                .map(|file| file.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+                .collect::<Vec<_>>();
+            paths.sort();
+            Ok((paths, files.skipped().len()))
+        };
+
+        assert_eq!(read(false)?, (vec![PathBuf::from("1.jpg")], 0));
+        assert_eq!(
+            read(true)?,
+            (
+                [".cache/3.jpg", ".hidden.jpg", "1.jpg", "sub/.4.jpg"]
+                    .map(PathBuf::from)
+                    .to_vec(),
+                1
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn read_extensions() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.JPG"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("3.cr2"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("4"), "2025:05:01 12:13:14");
+        let read = |extensions: &[&str]| -> Result<usize> {
+            let options = ReadOptions {
+                extensions: extensions.iter().map(|e| e.to_string()).collect(),
+                ..Default::default()
+            };
+            Ok(Files::read(dir.path(), &options)?.len())
+        };
+
+        assert_eq!(read(&[])?, 4);
+        assert_eq!(read(&["jpg"])?, 2);
+        assert_eq!(read(&["jpg", "CR2"])?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn read_path_list() -> Result<()> {
+        let list = io::Cursor::new("./a/1.jpg\n\n./b c/2.jpg\r\n./3.jpg");
+        assert_eq!(
+            super::read_path_list(list)?,
+            ["./a/1.jpg", "./b c/2.jpg", "./3.jpg"].map(PathBuf::from)
+        );
+        assert_eq!(
+            super::read_path_list(io::Cursor::new(""))?,
+            Vec::<PathBuf>::new()
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn read_path_list_non_utf8() -> Result<()> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let list = io::Cursor::new(b"./invalid \xFF.jpg\n./valid.jpg\n");
+        assert_eq!(
+            super::read_path_list(list)?,
+            [
+                PathBuf::from(OsStr::from_bytes(b"./invalid \xFF.jpg")),
+                PathBuf::from("./valid.jpg")
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn from_paths() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        write_photo(path("a/1.jpg"), "2025:05:03 12:13:14");
+        write_photo(path("b/2.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("3.png"), "2025:05:02 12:13:14");
+        fs::write(path("notes.txt"), "no exif here")?;
+        let paths = ["a/1.jpg", "b/2.jpg", "3.png", "notes.txt"].map(path);
+
+        let files = Files::from_paths(paths.clone(), &ReadOptions::default())?;
+        assert_eq!(
+            files.iter().map(|file| &file.path).collect::<Vec<_>>(),
+            vec![&paths[0], &paths[1], &paths[2]]
+        );
+        assert_eq!(files.skipped(), [path("notes.txt")]);
+
+        let options = ReadOptions {
+            extensions: vec!["jpg".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(Files::from_paths(paths, &options)?.len(), 2);
+        assert!(Files::from_paths([path("missing.jpg")], &ReadOptions::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn read_ignore_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.png"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("sub/3.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("sub/4.png"), "2025:05:01 12:13:14");
+        // Only the directory matches, so its files are left out by not descending into it
+        write_photo(dir.path().join("sub/exports/a/5.jpg"), "yesterday");
+        let read = || -> Result<Vec<PathBuf>> {
+            let mut paths = Files::read(dir.path(), &ReadOptions::default())?
+                .iter()
+                .map(|file| file.path.strip_prefix(dir.path()).unwrap().to_path_buf())
+                .collect::<Vec<_>>();
+            paths.sort();
+            Ok(paths)
+        };
+
+        fs::write(dir.path().join(IGNORE_NAME), "exports/\n*.png\n")?;
+        assert_eq!(read()?, ["1.jpg", "sub/3.jpg"].map(PathBuf::from));
+
+        fs::write(dir.path().join(IGNORE_NAME), "/sub\n")?;
+        assert_eq!(read()?, ["1.jpg", "2.png"].map(PathBuf::from));
+
+        fs::write(dir.path().join(IGNORE_NAME), "[")?;
+        assert!(Files::read(dir.path(), &ReadOptions::default()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn read_aborts_on_bad_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "yesterday");
+        write_photo(dir.path().join("3.jpg"), "2025:05:03 12:13:14");
+
+        let err = Files::read(dir.path(), &ReadOptions::default()).unwrap_err();
+        assert!(format!("{err:#}").contains("2.jpg"));
+        Ok(())
+    }
+
+    #[test]
+    fn read_skip_errors() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "yesterday");
+        write_photo(dir.path().join("3.jpg"), "2025:05:03 12:13:14");
+        let options = ReadOptions {
+            skip_errors: true,
+            ..Default::default()
+        };
+
+        let files = Files::read(dir.path(), &options)?;
+        assert_eq!(files.len(), 2);
+        assert_eq!(files.errors().len(), 1);
+        let (path, error) = &files.errors()[0];
+        assert_eq!(path, &dir.path().join("2.jpg"));
+        assert!(matches!(error, PhotoDaterError::DateParse { .. }));
+        Ok(())
+    }
+
+    #[test]
+    fn read_skipped() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        fs::write(dir.path().join("notes.txt"), "no exif here")?;
+
+        let files = Files::read(dir.path(), &ReadOptions::default())?;
+        assert_eq!(files.len(), 1);
+        assert_eq!(files.skipped(), [dir.path().join("notes.txt")]);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_moves() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 12:13:14");
+        let files = Files::read(dir.path(), &ReadOptions::default())?;
+        let plan = files
+            .move_by_days()
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+
+        // Move only the first file to inject a failure.
+        let RenamedFile(file, new_path) = &plan[0];
+        fs::create_dir_all(new_path.parent().unwrap())?;
+        fs::rename(&file.path, new_path)?;
+        assert_eq!(
+            Files::verify_moves(&plan, &ReadOptions::default()),
+            vec![MoveDiscrepancy::Missing(plan[1].1.clone())]
+        );
+
+        let RenamedFile(file, new_path) = &plan[1];
+        fs::create_dir_all(new_path.parent().unwrap())?;
+        fs::rename(&file.path, new_path)?;
+        assert_eq!(Files::verify_moves(&plan, &ReadOptions::default()), vec![]);
+
+        Ok(())
+    }
 }
@@ -1,16 +1,70 @@
 use anyhow::{anyhow, Result};
+use serde::Serialize;
 use std::{fmt::Display, str::FromStr};
 
 use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
 
+/// Time set as the end of intervals recognized from dates without time, see [`FilesInterval::try_split`].
+///
+/// Such intervals are displayed only by their dates, see [`FilesInterval::format`] and
+/// [`FilesInterval::to_date_only_display`], while intervals of files show the real time
+/// of the last file, even if it is this one.
+const END_OF_DAY: NaiveTime = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
+
 /// Represents a time interval between creation date of first and last photo.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize)]
 pub struct FilesInterval {
     pub from: NaiveDateTime,
     pub to: NaiveDateTime,
 }
 
-const SEPARATOR: &str = " - ";
+/// Format of dates in directory names.
+///
+/// The same format is used for parsing and formatting names, so a name
+/// formatted by [`FilesInterval::format`] is parsed back by [`FilesInterval::try_split`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameFormat {
+    /// Separator between year, month and day, e.g. `.` for `2025.05.01`
+    pub field_separator: char,
+    /// Separator between the first and the last date of a range
+    pub range_separator: String,
+    /// Follow the dates by the weekday of the first one, e.g. `2025-05-01 (Thursday)`
+    pub weekday: bool,
+}
+
+impl Default for NameFormat {
+    fn default() -> Self {
+        Self {
+            field_separator: '-',
+            range_separator: " - ".to_string(),
+            weekday: false,
+        }
+    }
+}
+
+impl NameFormat {
+    /// Format of a full date, e.g. `%Y-%m-%d`.
+    fn date(&self) -> String {
+        let s = self.field_separator;
+        format!("%Y{s}%m{s}%d")
+    }
+
+    /// Format of a date without year, e.g. `%m-%d`.
+    fn month_day(&self) -> String {
+        format!("%m{}%d", self.field_separator)
+    }
+
+    /// Format of a full timestamp used as a single-instant directory name prefix.
+    fn timestamp(&self) -> String {
+        format!("{}_%H-%M-%S", self.date())
+    }
+
+    /// Formats of a start time following a single date, e.g. `14-30` in `2025-05-01 14-30`.
+    const TIMES: [&str; 2] = ["%H-%M-%S", "%H-%M"];
+
+    /// Format of the weekday following the dates.
+    const WEEKDAY: &str = "(%A)";
+}
 
 impl FilesInterval {
     /// This method recognizes various directory naming patterns that include date ranges and splits
@@ -22,45 +76,107 @@ impl FilesInterval {
     /// - **Full range**: `"2025-05-01 - 2025-05-03 My Photos"` -> May 1st to 3rd 2025, remaining: "My Photos"
     /// - **Same year**: `"2025-05-01 - 05-03 My Photos"` -> May 1st to 3rd 2025, remaining: "My Photos"  
     /// - **Same month**: `"2025-05-01 - 03 My Photos"` -> May 1st to 3rd 2025, remaining: "My Photos"
+    /// - **Timestamp**: `"2025-05-01_14-30-00 My Photos"` -> May 1st 2025 at 14:30:00, remaining: "My Photos"
+    /// - **Start time**: `"2025-05-01 14-30 Concert"` -> May 1st 2025 from 14:30:00 to the end
+    ///   of the day, remaining: "Concert", seconds (`14-30-15`) are optional
     ///
     /// # Arguments
     ///
     /// * `name` - The directory name string to parse
+    /// * `format` - Separators of the dates, the examples use [`NameFormat::default`]
     ///
     /// # Returns
     ///
     /// Returns `Some((FilesInterval, &str))` if a valid date pattern is found, where the tuple contains
     /// the parsed date interval and the remaining name portion after the date.
     /// Returns `None` if no recognizable date pattern exists.
-    pub fn try_split(name: &str) -> Option<(Self, &str)> {
-        let (from, to, name) = name
+    pub fn try_split<'a>(name: &'a str, format: &NameFormat) -> Option<(Self, &'a str)> {
+        Self::parse_prefix(name, format).ok()
+    }
+
+    /// Parses an interval without any following name, in the formats of
+    /// [`FilesInterval::try_split`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `s` is not a date interval or continues after it.
+    pub fn parse(s: &str, format: &NameFormat) -> Result<Self> {
+        match Self::parse_prefix(s, format)? {
+            (interval, "") => Ok(interval),
+            (_, rest) => Err(anyhow!(
+                "Unexpected text {rest:?} after date interval in {s:?}"
+            )),
+        }
+    }
+
+    /// Parses the interval at the start of `name` like [`FilesInterval::try_split`],
+    /// describing why parsing failed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name does not start with a recognizable date pattern,
+    /// or if the last date of a range precedes the first one.
+    fn parse_prefix<'a>(name: &'a str, format: &NameFormat) -> Result<(Self, &'a str)> {
+        let date_format = format.date();
+        let parse = |date: &str| NaiveDate::parse_from_str(date, &date_format);
+        let s = format.field_separator;
+        let (interval, name) = name
             // Try if from and to differs.
-            .split_once(SEPARATOR)
-            .and_then(|(from, name)| name.split_once(' ').map(|(to, name)| (from, to, name)))
+            .split_once(format.range_separator.as_str())
+            .map(|(from, name)| {
+                let (to, name) = name.split_once(' ').unwrap_or((name, ""));
+                (from, to, name)
+            })
             .and_then(|(from, to, name)| {
-                let from = NaiveDate::from_str(from).ok()?;
+                let from = parse(from).ok()?;
                 // Check if date is `yyyy-mm-dd`
-                let to = NaiveDate::from_str(to)
+                let to = parse(to)
                     .or_else(|_| {
                         // Check if date is `mm-dd`
-                        NaiveDate::from_str(&format!("{:04}-{to}", from.year()))
+                        parse(&format!("{:04}{s}{to}", from.year()))
                     })
                     .or_else(|_| {
                         // Check if date is `dd`
-                        NaiveDate::from_str(&format!("{:04}-{:02}-{to}", from.year(), from.month()))
+                        parse(&format!("{:04}{s}{:02}{s}{to}", from.year(), from.month()))
                     })
                     .ok()?;
-                Some((from, to, name))
+                Some((Self::from_date(from, to), name))
+            })
+            // From and to are same day, optionally starting at the given time.
+            .or_else(|| {
+                let (from_str, name) = name.split_once(' ').unwrap_or((name, ""));
+                let from = parse(from_str).ok()?;
+                let (time, rest) = name.split_once(' ').unwrap_or((name, ""));
+                let time = NameFormat::TIMES
+                    .iter()
+                    .find_map(|time_format| NaiveTime::parse_from_str(time, time_format).ok());
+                Some(match time {
+                    Some(time) => (
+                        Self::from_date(from, from).map(|interval| Self {
+                            from: from.and_time(time),
+                            ..interval
+                        }),
+                        rest,
+                    ),
+                    None => (Self::from_date(from, from), name),
+                })
             })
-            // From and to are same day.
+            // From and to are same instant.
             .or_else(|| {
-                let (from_str, name) = name.split_once(' ')?;
-                let from = NaiveDate::from_str(from_str).ok()?;
-                Some((from, from, name))
-            })?;
-        Self::from_date(from, to)
-            .ok()
-            .map(|interval| (interval, name))
+                let (from_str, name) = name.split_once(' ').unwrap_or((name, ""));
+                let from = NaiveDateTime::parse_from_str(from_str, &format.timestamp()).ok()?;
+                Some((Ok(Self { from, to: from }), name))
+            })
+            .ok_or(anyhow!(
+                "Invalid date interval {name:?}, expected a date formatted as {date_format:?}"
+            ))?;
+        let interval = interval?;
+        let weekday = interval.from.format(NameFormat::WEEKDAY).to_string();
+        let name = match name.strip_prefix(&weekday) {
+            Some(name) if format.weekday => name.strip_prefix(' ').unwrap_or(name),
+            _ => name,
+        };
+        Ok((interval, name))
     }
 
     /// This method recognizes various directory naming patterns that include date ranges:
@@ -71,17 +187,95 @@ impl FilesInterval {
     /// - **Full range**: `"2025-05-01 - 2025-05-03 My Photos"` -> May 1st to 3rd, 2025
     /// - **Same year**: `"2025-05-01 - 05-03 My Photos"` -> May 1st to 3rd, 2025  
     /// - **Same month**: `"2025-05-01 - 03 My Photos"` -> May 1st to 3rd, 2025
+    /// - **Timestamp**: `"2025-05-01_14-30-00 My Photos"` -> May 1st 2025 at 14:30:00
+    /// - **Start time**: `"2025-05-01 14-30 Concert"` -> May 1st 2025 from 14:30:00
     ///
     /// # Arguments
     ///
     /// * `name` - The directory name string to parse
+    /// * `format` - Separators of the dates, the examples use [`NameFormat::default`]
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(FilesInterval)` if a valid date pattern is found, also after other
+    /// text (see [`FilesInterval::try_find`]), or `None` if no recognizable date pattern exists.
+    pub fn try_from_name(name: &str, format: &NameFormat) -> Option<Self> {
+        Self::try_find(name, format).map(|(_before, interval, _after)| interval)
+    }
+
+    /// Finds a date interval anywhere in the name, e.g. `"Trip 2025-05-01 - 03 Paris"`,
+    /// recognizing the same formats as [`FilesInterval::try_split`] does at the start.
+    ///
+    /// The interval has to start the name or follow a space. Shortened last dates of
+    /// ranges (`mm-dd`, `dd`) are recognized only right after the first date.
     ///
     /// # Returns
     ///
-    /// Returns `Some(FilesInterval)` if a valid date pattern is found,
-    /// or `None` if no recognizable date pattern exists.
-    pub fn try_from_name(name: &str) -> Option<Self> {
-        Self::try_split(name).map(|(interval, _name)| interval)
+    /// The text before the interval (without the separating space), the interval
+    /// and the text after it, or `None` if the first date in the name does not start
+    /// a valid interval.
+    pub fn try_find<'a>(name: &'a str, format: &NameFormat) -> Option<(&'a str, Self, &'a str)> {
+        let date_format = format.date();
+        std::iter::once(0)
+            .chain(name.match_indices(' ').map(|(i, _)| i + 1))
+            .map(|start| (start, &name[start..]))
+            .find(|(_, rest)| NaiveDate::parse_and_remainder(rest, &date_format).is_ok())
+            .and_then(|(start, rest)| {
+                let (interval, after) = Self::try_split(rest, format)?;
+                Some((name[..start].trim_end(), interval, after))
+            })
+    }
+
+    /// Formats the interval as a string suitable for directory names.
+    ///
+    /// This uses intelligent formatting to create compact, readable date ranges:
+    ///
+    /// # Formatting Rules
+    ///
+    /// - **Single day**: `"2025-05-01"`
+    /// - **Different years**: `"2025-05-01 - 2026-06-02"`
+    /// - **Same year, different months**: `"2025-05-01 - 06-02"`
+    /// - **Same month**: `"2025-05-01 - 02"`
+    ///
+    /// Separators and the weekday are taken from `format`, the examples use [`NameFormat::default`].
+    pub fn format(&self, format: &NameFormat) -> String {
+        let mut ret = self.from.format(&format.date()).to_string();
+        if self.from.date() != self.to.date() {
+            ret += &format.range_separator;
+            if self.from.year() != self.to.year() {
+                ret += &self.to.format(&format.date()).to_string();
+            } else if self.from.month() != self.to.month() {
+                ret += &self.to.format(&format.month_day()).to_string();
+            } else {
+                ret += &self.to.format("%d").to_string();
+            }
+        }
+        if format.weekday {
+            ret += " ";
+            ret += &self.from.format(NameFormat::WEEKDAY).to_string();
+        }
+        ret
+    }
+
+    /// Returns the smallest interval containing both this and the `other` interval.
+    pub fn merge(&self, other: &Self) -> Self {
+        Self {
+            from: self.from.min(other.from),
+            to: self.to.max(other.to),
+        }
+    }
+
+    /// Formats the end of the interval, leaving out the time if it is the end of the
+    /// day set for intervals recognized from dates only, see [`FilesInterval::try_split`].
+    ///
+    /// Meant for intervals recognized from names. Intervals of files show their real
+    /// end time, even if the last file was taken at `23:59:59`.
+    pub fn to_date_only_display(&self) -> String {
+        if self.to.time() == END_OF_DAY {
+            self.to.date().to_string()
+        } else {
+            self.to.to_string()
+        }
     }
 
     /// Calculates the time duration of this interval.
@@ -89,6 +283,42 @@ impl FilesInterval {
         self.to - self.from
     }
 
+    /// Returns the number of whole days of the interval, rounded down,
+    /// so a span of 47 hours is 1 day.
+    pub fn delta_days(&self) -> i64 {
+        self.delta().num_days()
+    }
+
+    /// Returns the number of whole hours of the interval, rounded down.
+    pub fn delta_hours(&self) -> i64 {
+        self.delta().num_hours()
+    }
+
+    /// Formats the length of the interval as an ISO 8601 duration, e.g. `P2DT3H`.
+    /// Fractions of a second are left out.
+    pub fn iso_duration(&self) -> String {
+        let seconds = self.delta().num_seconds();
+        let (days, hours) = (seconds / 86400, seconds % 86400 / 3600);
+        let (minutes, seconds) = (seconds % 3600 / 60, seconds % 60);
+        let mut duration = "P".to_string();
+        if days > 0 {
+            duration += &format!("{days}D");
+        }
+        if hours > 0 || minutes > 0 || seconds > 0 || days == 0 {
+            duration += "T";
+        }
+        if hours > 0 {
+            duration += &format!("{hours}H");
+        }
+        if minutes > 0 {
+            duration += &format!("{minutes}M");
+        }
+        if seconds > 0 || duration == "PT" {
+            duration += &format!("{seconds}S");
+        }
+        duration
+    }
+
     /// Creates a FilesInterval from start and end dates.
     ///
     /// This method constructs a FilesInterval where the start time begins at
@@ -109,38 +339,25 @@ impl FilesInterval {
         }
         Ok(Self {
             from: NaiveDateTime::new(from, NaiveTime::from_hms_opt(0, 0, 0).unwrap()),
-            to: NaiveDateTime::new(to, NaiveTime::from_hms_opt(23, 59, 59).unwrap()),
+            to: NaiveDateTime::new(to, END_OF_DAY),
         })
     }
 }
 
+impl FromStr for FilesInterval {
+    type Err = anyhow::Error;
+
+    /// Parses an interval without any following name, e.g. `2025-05-01 - 03`, in the
+    /// formats of [`FilesInterval::try_split`] with the default [`NameFormat`].
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s, &NameFormat::default())
+    }
+}
+
 impl Display for FilesInterval {
-    /// Formats the interval as a string suitable for directory names.
-    ///
-    /// This implementation uses intelligent formatting to create compact,
-    /// readable date ranges:
-    ///
-    /// # Formatting Rules
-    ///
-    /// - **Single day**: `"2025-05-01"`
-    /// - **Different years**: `"2025-05-01 - 2026-06-02"`
-    /// - **Same year, different months**: `"2025-05-01 - 06-02"`  
-    /// - **Same month**: `"2025-05-01 - 02"`
+    /// Formats the interval using the default [`NameFormat`], see [`FilesInterval::format`].
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut ret = self.from.format("%Y-%m-%d").to_string();
-        if self.from.date() == self.to.date() {
-            return f.write_str(&ret);
-        }
-        ret += SEPARATOR;
-        if self.from.year() != self.to.year() {
-            ret += &self.to.format("%Y-%m-%d").to_string();
-        } else if self.from.month() != self.to.month() {
-            ret += &self.to.format("%m-%d").to_string();
-        } else {
-            ret += &self.to.format("%d").to_string();
-        }
-
-        f.write_str(&ret)
+        f.write_str(&self.format(&NameFormat::default()))
     }
 }
 
@@ -167,42 +384,249 @@ mod tests {
     fn try_from_name() {
         // Basic usage
         assert_eq!(
-            FilesInterval::try_from_name("Some name without any date"),
+            FilesInterval::try_from_name("Some name without any date", &NameFormat::default()),
             None
         );
 
         assert_eq!(
-            FilesInterval::try_from_name("2025-05-01 Some name"),
+            FilesInterval::try_from_name("2025-05-01 Some name", &NameFormat::default()),
             Some(new_files_interval((2025, 5, 1), None)),
         );
 
         assert_eq!(
-            FilesInterval::try_from_name("2025-05-01 - 2026-06-01 Some name"),
+            FilesInterval::try_from_name(
+                "2025-05-01 - 2026-06-01 Some name",
+                &NameFormat::default()
+            ),
             Some(new_files_interval((2025, 5, 1), Some((2026, 6, 1)))),
         );
 
         assert_eq!(
-            FilesInterval::try_from_name("2025-05-01 - 06-01 Some name"),
+            FilesInterval::try_from_name("2025-05-01 - 06-01 Some name", &NameFormat::default()),
             Some(new_files_interval((2025, 5, 1), Some((2025, 6, 1)))),
         );
 
         // Edge cases
         assert_eq!(
-            FilesInterval::try_from_name("06-01 Name start with number"),
+            FilesInterval::try_from_name("06-01 Name start with number", &NameFormat::default()),
             None
         );
 
         assert_eq!(
-            FilesInterval::try_from_name("2025-05-01 - Name start with separator"),
+            FilesInterval::try_from_name(
+                "2025-05-01 - Name start with separator",
+                &NameFormat::default()
+            ),
             Some(new_files_interval((2025, 5, 1), None)),
         );
 
         assert_eq!(
-            FilesInterval::try_from_name("2025-05-02 - 2025-05-01 - Interval is not possilbe"),
+            FilesInterval::try_from_name(
+                "2025-05-02 - 2025-05-01 - Interval is not possilbe",
+                &NameFormat::default()
+            ),
             None,
         );
     }
 
+    #[test]
+    fn from_str() -> Result<()> {
+        assert_eq!(
+            "2025-05-01 - 03".parse::<FilesInterval>()?,
+            new_files_interval((2025, 5, 1), Some((2025, 5, 3)))
+        );
+        assert_eq!(
+            "2025-05-01".parse::<FilesInterval>()?,
+            new_files_interval((2025, 5, 1), None)
+        );
+        assert_eq!(
+            FilesInterval::from_str("2025-05-01_14-30-00")?.to,
+            "2025-05-01T14:30:00".parse()?
+        );
+
+        let error = |s: &str| FilesInterval::from_str(s).unwrap_err().to_string();
+        assert!(error("2025-05-01 - 03 Trip").contains("Unexpected text \"Trip\""));
+        assert!(error("Trip").contains("Invalid date interval \"Trip\""));
+        assert!(error("2025-05-03 - 01").contains("higher than to date"));
+        Ok(())
+    }
+
+    #[test]
+    fn try_split_start_time() {
+        let format = NameFormat::default();
+        let at = |date: &str| NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S").unwrap();
+
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01 14-30 Concert", &format),
+            Some((
+                FilesInterval {
+                    from: at("2025-05-01 14:30:00"),
+                    to: at("2025-05-01 23:59:59"),
+                },
+                "Concert"
+            ))
+        );
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01 14-30-15", &format),
+            Some((
+                FilesInterval {
+                    from: at("2025-05-01 14:30:15"),
+                    to: at("2025-05-01 23:59:59"),
+                },
+                ""
+            ))
+        );
+        // Not a time, stays part of the name
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01 25-30 Concert", &format),
+            Some((new_files_interval((2025, 5, 1), None), "25-30 Concert"))
+        );
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01 Concert", &format),
+            Some((new_files_interval((2025, 5, 1), None), "Concert"))
+        );
+    }
+
+    #[test]
+    fn try_find() {
+        let format = NameFormat::default();
+        let single = || Some(new_files_interval((2025, 5, 1), None));
+        let range = || Some(new_files_interval((2025, 5, 1), Some((2025, 5, 3))));
+        let find = |name| {
+            FilesInterval::try_find(name, &format)
+                .map(|(before, interval, after)| (before, Some(interval), after))
+        };
+
+        assert_eq!(find("2025-05-01 Trip"), Some(("", single(), "Trip")));
+        assert_eq!(find("Trip 2025-05-01"), Some(("Trip", single(), "")));
+        assert_eq!(
+            find("Trip 2025-05-01 Paris"),
+            Some(("Trip", single(), "Paris"))
+        );
+        assert_eq!(
+            find("Summer trip 2025-05-01 - 03 Paris"),
+            Some(("Summer trip", range(), "Paris"))
+        );
+        assert_eq!(find("Trip 2025-05-01 - 05-03"), Some(("Trip", range(), "")));
+        assert_eq!(
+            FilesInterval::try_from_name("Trip 2025-05-01 Paris", &format),
+            single()
+        );
+
+        // Shortened dates are not recognized on their own
+        assert_eq!(find("Trip 05-03 Paris"), None);
+        assert_eq!(find("Trip 03 2025-05-01"), Some(("Trip 03", single(), "")));
+        // The first date starts an impossible range, later dates are not searched
+        assert_eq!(find("Trip 2025-05-03 - 2025-05-01 Paris"), None);
+        assert_eq!(find("Trip without date"), None);
+    }
+
+    #[test]
+    fn try_split_timestamp() {
+        let instant = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2025, 5, 1).unwrap(),
+            NaiveTime::from_hms_opt(14, 30, 0).unwrap(),
+        );
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01_14-30-00 Event", &NameFormat::default()),
+            Some((
+                FilesInterval {
+                    from: instant,
+                    to: instant
+                },
+                "Event"
+            )),
+        );
+
+        assert_eq!(
+            FilesInterval::try_split(
+                "2025-05-01_14-30 Event without seconds",
+                &NameFormat::default()
+            ),
+            None
+        );
+
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01_25-30-00 Invalid time", &NameFormat::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn custom_format_round_trip() {
+        let format = NameFormat {
+            field_separator: '.',
+            range_separator: "_".to_string(),
+            weekday: false,
+        };
+        let intervals = [
+            new_files_interval((2025, 5, 1), None),
+            new_files_interval((2025, 5, 1), Some((2026, 6, 2))),
+            new_files_interval((2025, 5, 1), Some((2025, 6, 2))),
+            new_files_interval((2025, 5, 1), Some((2025, 5, 2))),
+        ];
+        assert_eq!(intervals[1].format(&format), "2025.05.01_2026.06.02");
+        assert_eq!(intervals[2].format(&format), "2025.05.01_06.02");
+        for interval in intervals {
+            let name = format!("{} Some name", interval.format(&format));
+            assert_eq!(
+                FilesInterval::try_split(&name, &format),
+                Some((interval, "Some name"))
+            );
+        }
+
+        // Names in other formats are not recognized
+        assert_eq!(
+            FilesInterval::try_from_name("2025-05-01 Some name", &format),
+            None
+        );
+        assert_eq!(
+            FilesInterval::try_from_name("2025.05.01 Some name", &NameFormat::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn weekday() {
+        let format = NameFormat {
+            weekday: true,
+            ..Default::default()
+        };
+        let day = new_files_interval((2025, 5, 1), None);
+        let range = new_files_interval((2025, 5, 1), Some((2025, 5, 4)));
+        assert_eq!(day.format(&format), "2025-05-01 (Thursday)");
+        assert_eq!(range.format(&format), "2025-05-01 - 04 (Thursday)");
+
+        for interval in [day, range] {
+            let name = format!("{} Trip", interval.format(&format));
+            assert_eq!(
+                FilesInterval::try_split(&name, &format),
+                Some((interval, "Trip"))
+            );
+        }
+
+        // Only the weekday of the first date is removed from the name
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01 (Friday) Trip", &format).map(|(_, name)| name),
+            Some("(Friday) Trip")
+        );
+        assert_eq!(
+            FilesInterval::try_split("2025-05-01 (Thursday) Trip", &NameFormat::default())
+                .map(|(_, name)| name),
+            Some("(Thursday) Trip")
+        );
+    }
+
+    #[test]
+    fn merge() {
+        let first = new_files_interval((2025, 5, 1), Some((2025, 5, 3)));
+        let second = new_files_interval((2025, 5, 2), Some((2025, 6, 1)));
+        let merged = new_files_interval((2025, 5, 1), Some((2025, 6, 1)));
+        assert_eq!(first.merge(&second), merged);
+        assert_eq!(second.merge(&first), merged);
+        assert_eq!(first.merge(&first), first);
+    }
+
     #[test]
     fn delta() {
         assert_eq!(
@@ -216,6 +640,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn delta_days_and_hours() -> Result<()> {
+        let interval = FilesInterval {
+            from: "2025-05-01T10:00:00".parse()?,
+            to: "2025-05-03T09:30:00".parse()?,
+        };
+        assert_eq!(interval.delta_days(), 1);
+        assert_eq!(interval.delta_hours(), 47);
+
+        let interval = new_files_interval((2025, 5, 1), None);
+        assert_eq!(interval.delta_days(), 0);
+        assert_eq!(interval.delta_hours(), 23);
+        Ok(())
+    }
+
+    #[test]
+    fn iso_duration() -> Result<()> {
+        let interval = |from: &str, to: &str| -> Result<FilesInterval> {
+            Ok(FilesInterval {
+                from: from.parse()?,
+                to: to.parse()?,
+            })
+        };
+
+        let span = interval("2025-05-01T10:00:00", "2025-05-03T13:00:00")?;
+        assert_eq!(span.iso_duration(), "P2DT3H");
+
+        let iso = |from, to| Ok::<_, anyhow::Error>(interval(from, to)?.iso_duration());
+        assert_eq!(iso("2025-05-01T10:00:00", "2025-05-01T10:00:00")?, "PT0S");
+        assert_eq!(iso("2025-05-01T10:00:00", "2025-05-03T10:00:00")?, "P2D");
+        assert_eq!(
+            iso("2025-05-01T10:00:00", "2025-05-01T10:20:05")?,
+            "PT20M5S"
+        );
+        assert_eq!(iso("2025-05-01T10:00:00", "2025-05-02T10:00:01")?, "P1DT1S");
+        Ok(())
+    }
+
+    #[test]
+    fn to_date_only_display() -> Result<()> {
+        let format = NameFormat::default();
+        let display = |name: &str| -> Result<String> {
+            Ok(FilesInterval::try_from_name(name, &format)
+                .ok_or(anyhow!("date not recognized in {name:?}"))?
+                .to_date_only_display())
+        };
+        assert_eq!(display("2025-05-01 - 03 Trip")?, "2025-05-03");
+        assert_eq!(
+            display("2025-05-01_14-30-00 Concert")?,
+            "2025-05-01 14:30:00"
+        );
+        Ok(())
+    }
+
     #[test]
     fn to_string() {
         assert_eq!(
@@ -0,0 +1,54 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use std::{fs, path::Path};
+
+/// Default name of the marker file stored in the target directory.
+pub const MARKER_NAME: &str = ".photo-dater-last-run";
+
+/// Format of the creation date stored in the marker file.
+const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+/// Reads the newest creation date processed by the previous run.
+///
+/// Returns `None` if the marker file does not exist yet.
+///
+/// # Errors
+///
+/// Returns an error if the marker exists but cannot be read or parsed.
+pub fn read(path: impl AsRef<Path>) -> Result<Option<NaiveDateTime>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    NaiveDateTime::parse_from_str(content.trim(), FORMAT)
+        .map(Some)
+        .context(format!("Invalid last run marker {path:?}"))
+}
+
+/// Stores the newest processed creation date into the marker file.
+pub fn write(path: impl AsRef<Path>, created: NaiveDateTime) -> Result<()> {
+    Ok(fs::write(path, format!("{}\n", created.format(FORMAT)))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn read_write() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(MARKER_NAME);
+        assert_eq!(read(&path)?, None);
+
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14")?;
+        write(&path, created)?;
+        assert_eq!(fs::read_to_string(&path)?, "2025-05-01T12:13:14\n");
+        assert_eq!(read(&path)?, Some(created));
+
+        fs::write(&path, "yesterday")?;
+        assert!(read(&path).is_err());
+        Ok(())
+    }
+}
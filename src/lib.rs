@@ -1,79 +1,524 @@
+mod calendar;
+mod config;
 mod directory;
+mod error;
 mod file;
+mod filename_date;
 mod files;
 mod files_interval;
+mod last_run;
+mod name_template;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod stable_index;
+mod target_fs;
+mod tee;
+#[cfg(test)]
+mod test_utils;
+mod tree;
+#[cfg(feature = "ratatui")]
+mod tui;
+mod undo_log;
+mod xmp;
 
+pub use crate::error::PhotoDaterError;
 use crate::{
+    config::Config,
     directory::Directory,
-    file::{ByCreatedDate, ByPath},
-    files::RenamedFile,
-    files_interval::FilesInterval,
+    file::{ByCreatedDate, ByFileName, ByPath},
+    files::{DateTree, Files, PlanCount, PlanSummary, ReadOptions, RenamedFile, RenamedFiles},
+    files_interval::{FilesInterval, NameFormat},
+    name_template::{DirectoryTemplate, NameTemplate},
+    stable_index::StableIndex,
+    target_fs::{NamePolicy, TargetFs},
+    tee::Tee,
+    undo_log::UndoLog,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use chrono::{NaiveDate, TimeDelta};
 use clap::{builder::styling::AnsiColor, Parser, Subcommand, ValueEnum};
 use file::File;
-use std::{ffi::OsString, fs, io, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    ffi::OsString,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// Maximum length of a histogram bar printed by `stats`.
+const HISTOGRAM_WIDTH: usize = 50;
+
+/// Format of the command output
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human readable text
+    #[default]
+    Text,
+    /// JSON document
+    Json,
+}
+
+/// Unit in which the length of an interval is printed by `interval` and limited by `check`
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum IntervalUnit {
+    /// Whole days, rounded down (a span of 47 hours is 1 day), e.g. `2 days`
+    #[default]
+    Days,
+    /// Whole hours, rounded down, e.g. `51 hours`
+    Hours,
+    /// ISO 8601 duration, e.g. `P2DT3H`, only printed
+    Iso,
+}
+
+impl IntervalUnit {
+    /// Returns the length of the interval in this unit, or `None` for
+    /// [`IntervalUnit::Iso`], which is not a single number.
+    fn delta(&self, interval: &FilesInterval) -> Option<i64> {
+        match self {
+            IntervalUnit::Days => Some(interval.delta_days()),
+            IntervalUnit::Hours => Some(interval.delta_hours()),
+            IntervalUnit::Iso => None,
+        }
+    }
+
+    /// Formats the length of the interval in this unit, e.g. `2 days`, `51 hours`
+    /// or `P2DT3H`.
+    fn format_delta(&self, interval: &FilesInterval) -> String {
+        match self.delta(interval) {
+            Some(delta) => format!("{delta} {}", self.name()),
+            None => interval.iso_duration(),
+        }
+    }
+
+    /// Returns the name of the unit used as a plural noun and a JSON key, e.g. `days`.
+    fn name(&self) -> &'static str {
+        match self {
+            IntervalUnit::Days => "days",
+            IntervalUnit::Hours => "hours",
+            IntervalUnit::Iso => "duration",
+        }
+    }
+
+    /// Checks that the length of the interval in this unit is within `min` and `max`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PhotoDaterError`] if the interval is out of the limits, or an error
+    /// for [`IntervalUnit::Iso`], whose durations cannot be compared to the limits.
+    fn check(&self, interval: &FilesInterval, min: Option<u32>, max: u32) -> Result<()> {
+        let (from, to) = (interval.from, interval.to);
+        let delta = self
+            .delta(interval)
+            .ok_or(anyhow!("Intervals can be checked only in days or hours"))?;
+        let days = *self == IntervalUnit::Days;
+        if delta.abs() > max.into() {
+            return Err(if days {
+                PhotoDaterError::IntervalTooLarge {
+                    from,
+                    to,
+                    days: delta,
+                    max,
+                }
+            } else {
+                PhotoDaterError::IntervalTooLargeHours {
+                    from,
+                    to,
+                    hours: delta,
+                    max,
+                }
+            }
+            .into());
+        }
+        match min {
+            Some(min) if delta.abs() < min.into() => Err(if days {
+                PhotoDaterError::IntervalTooSmall {
+                    from,
+                    to,
+                    days: delta,
+                    min,
+                }
+            } else {
+                PhotoDaterError::IntervalTooSmallHours {
+                    from,
+                    to,
+                    hours: delta,
+                    min,
+                }
+            }
+            .into()),
+            _ => Ok(()),
+        }
+    }
+}
 
-#[derive(ValueEnum, Debug, Clone)]
-enum RenameFileSort {
+/// Criterion used to order files
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
+// Named after the ordering wrappers, which also gives the `by-*` command-line values
+#[allow(clippy::enum_variant_names)]
+enum SortKey {
+    #[value(alias = "path")]
     ByPath,
+    #[value(alias = "file-name")]
+    ByFileName,
+    #[value(alias = "created")]
     ByCreatedDate,
 }
 
+impl SortKey {
+    /// Compares two files using the ordering wrapper matching this key.
+    fn compare(&self, a: &File, b: &File) -> Ordering {
+        match self {
+            SortKey::ByPath => ByPath(a).cmp(&ByPath(b)),
+            SortKey::ByFileName => ByFileName(a).cmp(&ByFileName(b)),
+            SortKey::ByCreatedDate => ByCreatedDate(a).cmp(&ByCreatedDate(b)),
+        }
+    }
+
+    /// Plans sequential renames of `files` in the order given by this key (reversed if
+    /// `reverse` is set), named by `template` and numbered by `index`,
+    /// see [`Files::rename_files_stable`].
+    fn rename_files<'a>(
+        &self,
+        files: &'a Files,
+        name: &str,
+        digits: Option<usize>,
+        template: &NameTemplate,
+        index: &mut StableIndex,
+        reverse: bool,
+    ) -> Result<RenamedFiles<'a>> {
+        match self {
+            SortKey::ByPath => {
+                files.rename_files_stable::<ByPath<&File>>(name, digits, template, index, reverse)
+            }
+            SortKey::ByFileName => files
+                .rename_files_stable::<ByFileName<&File>>(name, digits, template, index, reverse),
+            SortKey::ByCreatedDate => files.rename_files_stable::<ByCreatedDate<&File>>(
+                name, digits, template, index, reverse,
+            ),
+        }
+    }
+
+    /// Compares two files by each key in turn, using later keys only to break ties.
+    fn compare_all(keys: &[SortKey], a: &File, b: &File) -> Ordering {
+        keys.iter().fold(Ordering::Equal, |ord, key| {
+            ord.then_with(|| key.compare(a, b))
+        })
+    }
+}
+
 /// Available commands
-#[derive(Subcommand, Clone, Debug)]
+#[derive(Subcommand, Clone, Debug, PartialEq, Eq)]
 enum Commands {
     /// Check the status of directory naming based on contained files' dates
-    Status,
+    Status {
+        /// Check the name of each immediate subdirectory against its own files instead
+        #[arg(long)]
+        all: bool,
+    },
 
     /// Rename directory based on the date range of contained files
     Rename {
         /// Maximum allowed interval in days between oldest and newest files
         #[arg(default_value = "0")]
         max_interval: u32,
-        /// Preview the rename operation without actually performing it
-        #[arg(short = 'D', long)]
-        dry_run: bool,
+        /// Append a number (e.g. `2025-05-01 Trip (1)`) when the new name is already taken,
+        /// instead of failing
+        #[arg(long)]
+        suffix_on_conflict: bool,
+        /// Layout of the new directory name with placeholders {name} (the current name
+        /// without its dates) and {interval}, e.g. "{name} [{interval}]"
+        #[arg(long)]
+        template: Option<DirectoryTemplate>,
+    },
+
+    /// Replace the leading date of the directory name by the date range of contained files,
+    /// e.g. `2025-05-01 Trip` becomes `2025-05-01 - 03 Trip` after adding photos
+    RenameInterval {
+        /// Maximum allowed interval in days between oldest and newest files
+        #[arg(default_value = "0")]
+        max_interval: u32,
+        /// Append a number (e.g. `2025-05-01 Trip (1)`) when the new name is already taken,
+        /// instead of failing
+        #[arg(long)]
+        suffix_on_conflict: bool,
     },
 
     /// List all files in the directory sorted by creation date
-    List,
+    List {
+        /// Comma-separated sorting criteria, later keys break ties of earlier ones
+        #[arg(short, long, value_delimiter = ',', default_value = "by-created-date")]
+        sort_by: Vec<SortKey>,
+        /// List files in reverse order, e.g. newest first
+        #[arg(short, long)]
+        reverse: bool,
+        /// Show where each creation date was taken from (`exif` or `filename`)
+        #[arg(long)]
+        with_source: bool,
+        /// Print only paths, each terminated by a NUL byte instead of a newline (e.g. for `xargs -0`)
+        #[arg(short = '0', long, conflicts_with = "with_source")]
+        null: bool,
+        /// Print absolute paths with symbolic links resolved, usable from any working directory
+        #[arg(short, long)]
+        absolute: bool,
+    },
 
     /// Display the date interval (range) of files in the directory
-    Interval,
+    Interval {
+        /// Unit of the printed length of the interval
+        #[arg(short, long, value_enum, default_value_t)]
+        unit: IntervalUnit,
+        /// Print the number of files of each day in the interval, including days
+        /// without files (e.g. `2025-05-02: 0`)
+        #[arg(long)]
+        per_day_counts: bool,
+        /// Leave days without files out of `--per-day-counts`
+        #[arg(long, requires = "per_day_counts")]
+        nonzero_only: bool,
+    },
+
+    /// Summarize file counts, the date span and a per-month histogram of creation dates
+    Stats,
+
+    /// Print the number of files with dates and how many of them have each extension
+    Count,
+
+    /// Print the first and the last capture time of each day and the time elapsed between them
+    DaySpans,
 
     /// Check if the file date interval is within acceptable limits
     Check {
-        /// Maximum allowed interval in days
+        /// Maximum allowed interval, in whole days rounded down (so 47 hours pass
+        /// `check 1`) unless another `--unit` is given
         max_interval: u32,
+        /// Minimum required interval, e.g. to detect directories of separate events
+        /// merged by mistake
+        #[arg(long, value_name = "MIN")]
+        min_interval: Option<u32>,
+        /// Unit of the minimum and maximum interval
+        #[arg(short, long, value_enum, default_value_t)]
+        unit: IntervalUnit,
+        /// Do not print "OK" when the check passes, rely on the exit code only
+        #[arg(short, long)]
+        quiet: bool,
     },
 
     /// Rename individual files with sequential numbering
     FilesRename {
-        /// Preview the rename operation without actually performing it
-        #[arg(short = 'D', long)]
-        dry_run: bool,
         /// Base name for renaming files (uses directory name if not provided)
         #[arg(short, long)]
         name: Option<String>,
-        /// Sorting criterion for file renaming (by-path or by-created-date)
+        /// Sorting criterion for file renaming (by-path, by-file-name ignoring the directory,
+        /// or by-created-date)
         #[arg(short, long, default_value = "by-path")]
-        sort_by: RenameFileSort,
-        /// Number of digits for zero-padding sequential numbers.
-        /// If not specified automatically calculates based on the total number of files.
+        sort_by: SortKey,
+        /// Number files in reverse order, e.g. newest first
+        #[arg(short, long)]
+        reverse: bool,
+        /// Number of digits for zero-padding sequential numbers, at least as many as the
+        /// highest number has. If not specified automatically calculates based on the
+        /// total number of files.
         #[arg(short, long)]
         digits: Option<usize>,
         /// Skip extracting dates from names (applies to both user-provided names and directory names)
         #[arg(short = 'S', long)]
         skip_date: bool,
+        /// Number of the first renamed file, e.g. 51 to continue after `photo 50`
+        #[arg(
+            long,
+            visible_alias = "numbering-start",
+            value_name = "N",
+            default_value_t = 1
+        )]
+        start: usize,
+        /// File mapping file names to sequence numbers. Files listed there keep their
+        /// number, new files are numbered after the highest one and added to the file.
+        /// Renamed files are saved under their new names.
+        #[arg(long, value_name = "MAP")]
+        stable_index: Option<PathBuf>,
+        /// Only check that the new names sorted alphabetically follow the creation date order,
+        /// without renaming anything. Fails if they do not
+        #[arg(long)]
+        validate_order: bool,
+        /// Convert file extensions to lowercase (e.g. `IMG.JPG` gets `.jpg`) instead of keeping their case
+        #[arg(long)]
+        lowercase_ext: bool,
+        /// Number files independently within each directory they are currently in,
+        /// instead of across the whole tree
+        #[arg(long, conflicts_with = "stable_index")]
+        scoped: bool,
+        /// Layout of new file names, e.g. `{name}_{index}_{date:%Y%m%d}{ext}`. `{index}` is
+        /// padded to `--digits`, `{date}` takes an optional `strftime` format and `{ext}`
+        /// includes the dot. Literal braces are written as `{{` and `}}`.
+        /// [default: `{name} {index}{ext}`]
+        #[arg(long, value_name = "TEMPLATE")]
+        name_template: Option<NameTemplate>,
+        /// Turn new names into lowercase slugs without spaces and punctuation,
+        /// e.g. `photo-0001.jpg` instead of `Photo 0001.jpg`. The extension is kept as is.
+        #[arg(long, visible_alias = "slugify")]
+        slug: bool,
+    },
+
+    /// Report consecutive files (by path) whose creation date goes backwards
+    ClockCheck,
+
+    /// Report files whose EXIF date differs from their filesystem modification time,
+    /// e.g. because of a wrongly set camera clock
+    Verify {
+        /// Maximum allowed difference in days
+        #[arg(long, default_value = "7")]
+        max_drift_days: u32,
+    },
+
+    /// Report sets of files with identical content, e.g. photos imported twice
+    Dedupe {
+        /// Remove all but the first file (by path) of each set
+        #[arg(long)]
+        delete: bool,
+    },
+
+    /// Preview the day directories `move-by-days` would create and the number of
+    /// files moved into each, without moving anything
+    Tree {
+        /// List names of the files under each day directory
+        #[arg(long)]
+        names: bool,
+        /// Preview `move-by-days --flatten`
+        #[arg(long)]
+        flatten: bool,
+        /// Preview `move-by-days --out`
+        #[arg(long, value_name = "DIR", conflicts_with = "flatten")]
+        out: Option<PathBuf>,
+    },
+
+    /// Print a calendar of the year with the number of photos taken each day
+    Calendar {
+        /// Year to display
+        year: i32,
     },
 
     /// Move files into subdirectories organized by creation date
     MoveByDays {
-        /// Preview the move operation without actually performing it
-        #[arg(short = 'D', long)]
-        dry_run: bool,
+        /// Re-read the directory after moving and confirm every file reached its destination
+        #[arg(long)]
+        verify_after: bool,
+        /// Move only one of files with identical content taken on the same day,
+        /// leaving the duplicates in place
+        #[arg(long)]
+        dedupe_content: bool,
+        /// Create date directories directly in the target directory, also for files
+        /// from its subdirectories, numbering files with the same name (e.g. `IMG_001_1.jpg`)
+        #[arg(long)]
+        flatten: bool,
+        /// Create date directories in this directory instead of next to the files, numbering
+        /// files with the same name like `--flatten`
+        #[arg(
+            long,
+            visible_alias = "parent-dir",
+            value_name = "DIR",
+            conflicts_with = "flatten"
+        )]
+        out: Option<PathBuf>,
+    },
+
+    /// Move files into subdirectories organized by creation month (YYYY-MM)
+    MoveByMonth {
+        /// Re-read the directory after moving and confirm every file reached its destination
+        #[arg(long)]
+        verify_after: bool,
+    },
+
+    /// Move files into subdirectories organized by creation date and GPS position
+    /// (e.g. `2025-05-01 48.85,2.35`), files without position by creation date only
+    MoveByLocation {
+        /// Re-read the directory after moving and confirm every file reached its destination
+        #[arg(long)]
+        verify_after: bool,
+        /// Number of decimal places the coordinates are rounded to (2 places are about 1 km)
+        #[arg(long, default_value_t = 2)]
+        decimals: usize,
+    },
+
+    /// Move files into nested year/month/day subdirectories (e.g. `2025/05/01`)
+    MoveByDateTree {
+        /// Nesting depth of the date subdirectories
+        #[arg(short, long, default_value = "year/month/day")]
+        levels: DateTree,
+        /// Re-read the directory after moving and confirm every file reached its destination
+        #[arg(long)]
+        verify_after: bool,
+    },
+
+    /// Remove empty date-named subdirectories (e.g. `2025-05-01`), such as those left
+    /// after deleting moved photos
+    PruneEmpty,
+
+    /// Suggest merging date-named subdirectories of close days (e.g. `2025-05-01`,
+    /// `2025-05-02`) into one directory named by the date range, without changing anything
+    SuggestMerge {
+        /// Maximum number of days between the subdirectories merged together
+        #[arg(default_value = "1")]
+        max_gap: u32,
     },
+
+    /// Apply renames and moves planned by a previous run with `--dry-run-json`
+    /// (or `--dry-run --format json`), aborting if any of them conflicts
+    Apply {
+        /// Plan written by the previous run
+        #[arg(long)]
+        plan: PathBuf,
+    },
+
+    /// Revert renames and moves recorded by a previous run with `--undo-log`
+    Undo {
+        /// Undo log written by the previous run
+        log: PathBuf,
+    },
+}
+
+impl Commands {
+    /// Returns `true` if the command only previews changes without touching the filesystem,
+    /// even without `--dry-run`.
+    fn dry_run(&self) -> bool {
+        matches!(
+            self,
+            Commands::FilesRename {
+                validate_order: true,
+                ..
+            }
+        )
+    }
+
+    /// Returns `true` if the command renames or inspects the target directory itself,
+    /// so it cannot read files listed by `--files-from` instead.
+    fn needs_directory(&self) -> bool {
+        matches!(
+            self,
+            Commands::Status { .. }
+                | Commands::Rename { .. }
+                | Commands::RenameInterval { .. }
+                | Commands::PruneEmpty
+                | Commands::SuggestMerge { .. }
+        )
+    }
+
+    /// Returns `true` if the command renames, moves or removes files or directories
+    /// relative to the target directory, so it cannot span multiple directories.
+    fn single_directory(&self) -> bool {
+        matches!(
+            self,
+            Commands::Status { .. }
+                | Commands::Rename { .. }
+                | Commands::RenameInterval { .. }
+                | Commands::FilesRename { .. }
+                | Commands::MoveByDays { .. }
+                | Commands::MoveByMonth { .. }
+                | Commands::MoveByLocation { .. }
+                | Commands::MoveByDateTree { .. }
+                | Commands::PruneEmpty
+                | Commands::SuggestMerge { .. }
+        )
+    }
 }
 
 /// Command-line interface structure
@@ -84,11 +529,202 @@ struct Cli {
     #[arg(default_value = ".")]
     directory: PathBuf,
 
+    /// Additional directory whose files are combined with the target directory, can be
+    /// repeated (e.g. `--extra-dir ../day2`), not supported by commands changing directories
+    #[arg(long = "extra-dir", global = true, value_name = "DIRECTORY")]
+    extra_dirs: Vec<PathBuf>,
+
+    /// Read the files listed in this file, one path per line, instead of the target
+    /// directory (`-` reads the list from stdin, e.g. `find . -name '*.jpg' | photo-dater
+    /// --files-from - list`). Not supported by commands renaming or inspecting the
+    /// directory itself, e.g. `rename` and `status`.
+    #[arg(
+        long,
+        global = true,
+        value_name = "LIST",
+        conflicts_with = "extra_dirs"
+    )]
+    files_from: Option<PathBuf>,
+
+    /// Read only files directly inside the target directory, without descending into subdirectories
+    #[arg(long, global = true)]
+    no_recursive: bool,
+
+    /// Descend into subdirectories even if the config file sets `no-recursive`
+    #[arg(long, global = true, conflicts_with = "no_recursive")]
+    recursive: bool,
+
+    /// Read files and descend into directories behind symbolic links, which are skipped otherwise
+    #[arg(long, global = true)]
+    follow_symlinks: bool,
+
+    /// Read hidden files and descend into hidden directories (e.g. `.thumbnails`),
+    /// which are skipped otherwise
+    #[arg(long, global = true)]
+    dot_files: bool,
+
+    /// Skip files that cannot be read (e.g. a corrupt EXIF date) instead of failing,
+    /// reporting them at the end of reading
+    #[arg(long, global = true)]
+    skip_errors: bool,
+
+    /// Treat EXIF dates as UTC and convert them to the time zone given by `--to-timezone`
+    #[arg(
+        long,
+        global = true,
+        requires = "to_timezone",
+        conflicts_with = "tz",
+        group = "from_timezone"
+    )]
+    assume_utc: bool,
+
+    /// IANA time zone (e.g. `America/New_York`) the camera clock was set to. EXIF dates
+    /// without an offset are taken as local times of this zone.
+    #[arg(
+        long,
+        global = true,
+        visible_alias = "timezone",
+        group = "from_timezone"
+    )]
+    tz: Option<chrono_tz::Tz>,
+
+    /// IANA time zone (e.g. `Europe/Prague`) to convert EXIF dates into, from UTC with
+    /// `--assume-utc` or from the zone given by `--tz`
+    #[arg(long, global = true, requires = "from_timezone")]
+    to_timezone: Option<chrono_tz::Tz>,
+
+    /// Use dates from messaging app file names (e.g. WhatsApp `IMG-20250501-WA0001.jpg`)
+    /// for files without EXIF date
+    #[arg(long, global = true)]
+    name_dates: bool,
+
+    /// Process only files with this extension, can be repeated (e.g. `--ext jpg --ext cr2`)
+    #[arg(long = "ext", global = true, value_name = "EXT")]
+    extensions: Vec<String>,
+
+    /// Process only files created after the newest file of the previous `--since-last` run
+    #[arg(long, global = true)]
+    since_last: bool,
+
+    /// Process only files created on this day or later (e.g. `2025-05-01`)
+    #[arg(long, global = true, value_name = "DATE")]
+    since: Option<NaiveDate>,
+
+    /// Process only files created on this day or earlier (e.g. `2025-05-31`)
+    #[arg(long, global = true, value_name = "DATE")]
+    until: Option<NaiveDate>,
+
+    /// Marker file storing the newest processed creation date for `--since-last`
+    /// (defaults to `.photo-dater-last-run` in the target directory)
+    #[arg(long, global = true, requires = "since_last")]
+    marker: Option<PathBuf>,
+
+    /// Filesystem whose file name restrictions (length, illegal characters) generated names must follow
+    #[arg(long, global = true, value_enum)]
+    target_fs: Option<TargetFs>,
+
+    /// Replace illegal characters and truncate too long names instead of failing
+    #[arg(long, global = true, requires = "target_fs")]
+    fix_names: bool,
+
+    /// Separator between year, month and day in directory names (e.g. `.` for `2025.05.01`)
+    #[arg(long, global = true, value_name = "CHAR", default_value_t = '-')]
+    date_separator: char,
+
+    /// Separator between the first and the last date of a range in directory names
+    #[arg(long, global = true, value_name = "SEP", default_value = " - ")]
+    range_separator: String,
+
+    /// Follow dates in directory names by the weekday, e.g. `2025-05-01 (Thursday) Trip`
+    #[arg(long, global = true)]
+    with_weekday: bool,
+
+    /// Number of threads reading files in parallel (defaults to the number of CPUs)
+    #[arg(short, long, global = true)]
+    jobs: Option<usize>,
+
+    /// Output format of the `list` and `interval` commands
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// Print additional information, such as the number of files skipped for missing dates
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Review planned renames and moves in a terminal UI and choose which to apply
+    #[cfg(feature = "ratatui")]
+    #[arg(long, global = true)]
+    tui: bool,
+
+    /// Record applied renames and moves to this file, so they can be reverted by `undo`
+    #[arg(long, global = true, value_name = "LOG")]
+    undo_log: Option<PathBuf>,
+
+    /// Append error and warning messages also to this file
+    #[arg(long, global = true)]
+    error_log: Option<PathBuf>,
+
+    /// Preview renames, moves and removals of any command without changing anything
+    #[arg(short = 'D', long, visible_alias = "no-act", global = true)]
+    dry_run: bool,
+
+    /// Preview renames and moves of any command, printing planned operations as a
+    /// JSON array of `{"from", "to"}` objects instead of text
+    #[arg(long, global = true)]
+    dry_run_json: bool,
+
+    /// Ignore `.photo-dater.toml` files in the target directory and its ancestors
+    #[arg(long, global = true)]
+    no_config: bool,
+
     /// The command to execute
     #[command(subcommand)]
     cmd: Commands,
 }
 
+/// Returns the canonical form of `path`, or `path` made absolute against the current
+/// directory if it cannot be canonicalized (e.g. a broken symbolic link), noting the
+/// failure in `err`.
+fn absolute_path(path: &Path, mut err: impl Write) -> Result<PathBuf> {
+    match fs::canonicalize(path) {
+        Ok(canonical) => Ok(canonical),
+        Err(e) => {
+            writeln!(err, "Cannot canonicalize {path:?}: {e}")?;
+            Ok(std::path::absolute(path)?)
+        }
+    }
+}
+
+/// Writes planned renames and moves as a JSON array of [`undo_log::Entry`] objects.
+fn write_plan_json<'a>(
+    mut std: impl Write,
+    plan: impl Iterator<Item = (&'a Path, &'a PathBuf)>,
+) -> Result<()> {
+    let plan = plan
+        .map(|(from, to)| undo_log::Entry {
+            from: from.to_path_buf(),
+            to: to.clone(),
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_writer(&mut std, &plan)?;
+    Ok(writeln!(std)?)
+}
+
+/// Describes the name status of `directory` for `status --format json`, with an
+/// error in place of the status if it cannot be determined.
+fn status_json(directory: &Directory, format: &NameFormat) -> serde_json::Value {
+    let path = directory.directory.to_string_lossy();
+    match directory.name_intervals(format) {
+        Ok((status, named, interval)) => serde_json::json!({
+            "directory": path,
+            "status": status,
+            "name_interval": named.map(|named| named.format(format)),
+            "files_interval": interval.format(format),
+        }),
+        Err(e) => serde_json::json!({"directory": path, "error": e.to_string()}),
+    }
+}
+
 fn help_colors() -> clap::builder::Styles {
     clap::builder::Styles::styled()
         .usage(AnsiColor::Green.on_default().bold())
@@ -111,125 +747,2539 @@ fn help_colors() -> clap::builder::Styles {
 /// * `args` - Iterator over command-line arguments
 /// * `std` - Writer for standard output messages
 /// * `err` - Writer for error and status messages
-pub fn run<I, T, WStd, WErr>(args: I, mut std: WStd, mut err: WErr) -> Result<()>
+pub fn run<I, T, WStd, WErr>(args: I, std: WStd, err: WErr) -> Result<()>
 where
     I: IntoIterator<Item = T>,
     T: Into<OsString> + Clone,
     WStd: io::Write,
     WErr: io::Write,
 {
-    let Cli { cmd, directory } = Cli::parse_from(args);
-    let directory = Directory::try_from(directory)?;
+    let cli = Cli::parse_from(args);
+    let error_log = cli
+        .error_log
+        .as_ref()
+        .map(|path| fs::File::options().create(true).append(true).open(path))
+        .transpose()?;
+    let mut err = Tee::new(err, error_log);
+    let result = execute(cli, io::stdin().lock(), std, &mut err);
+    if let (Err(e), Some(log)) = (&result, err.secondary_mut()) {
+        writeln!(log, "Error: {e:?}")?;
+    }
+    result
+}
+
+/// Executes the command of the parsed `cli`, see [`run`]. `stdin` is read by
+/// `--files-from -`.
+fn execute<RStd, WStd, WErr>(cli: Cli, stdin: RStd, mut std: WStd, mut err: WErr) -> Result<()>
+where
+    RStd: io::BufRead,
+    WStd: io::Write,
+    WErr: io::Write,
+{
+    let Cli {
+        cmd,
+        directory,
+        extra_dirs,
+        files_from,
+        no_recursive,
+        recursive,
+        assume_utc,
+        tz,
+        to_timezone,
+        name_dates,
+        extensions,
+        since_last,
+        since,
+        until,
+        marker,
+        target_fs,
+        fix_names,
+        date_separator,
+        range_separator,
+        with_weekday,
+        jobs,
+        format,
+        verbose,
+        #[cfg(feature = "ratatui")]
+        tui,
+        undo_log,
+        error_log: _,
+        dry_run,
+        dry_run_json,
+        follow_symlinks,
+        dot_files,
+        skip_errors,
+        no_config,
+    } = cli;
+    let name_policy = target_fs.map(|target| NamePolicy {
+        target,
+        fix: fix_names,
+    });
+    let name_format = NameFormat {
+        field_separator: date_separator,
+        range_separator,
+        weekday: with_weekday,
+    };
+    let dry_run = dry_run || cmd.dry_run();
+    let dry_run_json = dry_run_json || (dry_run && format == OutputFormat::Json);
+    let dry_run = dry_run || dry_run_json;
+    if let Commands::Undo { log } = &cmd {
+        let entries = if dry_run {
+            let mut entries = undo_log::read(log)?;
+            entries.reverse();
+            entries
+        } else {
+            undo_log::undo(log)?
+        };
+        if dry_run_json {
+            let plan = entries.iter().map(|entry| (&*entry.to, &entry.from));
+            return write_plan_json(&mut std, plan);
+        }
+        return entries
+            .into_iter()
+            .try_for_each(|undo_log::Entry { from, to }| {
+                writeln!(std, "Move back {to:?} => {from:?}")
+            })
+            .map_err(Into::into);
+    }
+    if let Commands::Apply { plan } = &cmd {
+        let plan = undo_log::read_plan(plan)?;
+        if dry_run {
+            undo_log::check(&plan)?;
+        } else {
+            let mut log = undo_log.map(UndoLog::open).transpose()?;
+            undo_log::apply(&plan, log.as_mut())?;
+        }
+        if dry_run_json {
+            let plan = plan.iter().map(|entry| (&*entry.from, &entry.to));
+            return write_plan_json(&mut std, plan);
+        }
+        return plan
+            .iter()
+            .try_for_each(|undo_log::Entry { from, to }| {
+                writeln!(std, "Move file {from:?} => {to:?}")
+            })
+            .map_err(Into::into);
+    }
+    if !extra_dirs.is_empty() && cmd.single_directory() {
+        return Err(anyhow!(
+            "The command works on a single directory and cannot be used with --extra-dir"
+        ));
+    }
+    if files_from.is_some() && cmd.needs_directory() {
+        return Err(anyhow!(
+            "The command works on a directory and cannot be used with --files-from"
+        ));
+    }
+    let config_path = (!no_config)
+        .then(|| Config::find(&directory))
+        .transpose()?
+        .flatten();
+    let config = config_path
+        .as_ref()
+        .map(Config::read)
+        .transpose()?
+        .unwrap_or_default();
+    if let Some(path) = config_path.as_ref().filter(|_| verbose) {
+        writeln!(err, "Using config file {path:?}")?;
+    }
+    let no_recursive = match (no_recursive, recursive) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    let no_recursive = no_recursive.or(config.no_recursive).unwrap_or_default();
+    let extensions = if extensions.is_empty() {
+        config.extensions.unwrap_or_default()
+    } else {
+        extensions
+    };
+    let read_options = ReadOptions {
+        max_depth: no_recursive.then_some(1),
+        utc_to: to_timezone.filter(|_| assume_utc || tz.is_some()),
+        timezone: tz,
+        name_dates,
+        jobs,
+        extensions,
+        follow_symlinks,
+        dot_files,
+        skip_errors,
+    };
+    let mut directory = match files_from {
+        Some(list) => {
+            let paths = if list.as_os_str() == "-" {
+                files::read_path_list(stdin)?
+            } else {
+                files::read_path_list(io::BufReader::new(fs::File::open(list)?))?
+            };
+            Directory::from_files(directory, Files::from_paths(paths, &read_options)?)
+        }
+        None => Directory::try_from(directory, &read_options)?,
+    };
+    for extra in extra_dirs {
+        let extra = Directory::try_from(extra, &read_options)?;
+        directory.files_mut().append(extra.into_files());
+    }
+    let errors = directory.get_files().errors();
+    if !errors.is_empty() {
+        writeln!(
+            err,
+            "Skipped {} files that could not be read:",
+            errors.len()
+        )?;
+        errors
+            .iter()
+            .try_for_each(|(path, e)| writeln!(err, "  {path:?}: {e}"))?;
+    }
+    let marker = since_last
+        .then(|| marker.unwrap_or_else(|| directory.directory.join(last_run::MARKER_NAME)));
+    if let Some(last_run) = marker.as_ref().map(last_run::read).transpose()?.flatten() {
+        directory.files_mut().retain_after(last_run);
+    }
+    if since.is_some() || until.is_some() {
+        let files = directory.files_mut();
+        let read = files.len();
+        files.retain_range(since, until);
+        if read > 0 && files.is_empty() {
+            return Err(PhotoDaterError::NoFilesInRange.into());
+        }
+    }
+    let mut undo_log = undo_log
+        .filter(|_| !dry_run)
+        .map(UndoLog::open)
+        .transpose()?;
+    let mut record = |from: &Path, to: &Path| match &mut undo_log {
+        Some(log) => log.record(from, to),
+        None => Ok(()),
+    };
+    let skipped = directory.get_files().skipped().len();
+    if verbose && skipped > 0 && matches!(cmd, Commands::Status { .. } | Commands::List { .. }) {
+        writeln!(err, "Skipped {skipped} files without dates")?;
+    }
     match cmd {
-        Commands::Status => match directory.name_status() {
-            Ok(directory::NameStatus::Valid) => writeln!(std, "Date is valid")?,
-            Ok(directory::NameStatus::Invalid) => writeln!(std, "Date is set but is invalid")?,
-            Ok(directory::NameStatus::SuperSet) => writeln!(std, "Date is set but is superset")?,
-            Ok(directory::NameStatus::None) => writeln!(std, "Date is not set")?,
+        Commands::Status { all } if format == OutputFormat::Json => {
+            let value = if all {
+                directory
+                    .subdirectories(&read_options)?
+                    .iter()
+                    .map(|subdirectory| status_json(subdirectory, &name_format))
+                    .collect()
+            } else {
+                status_json(&directory, &name_format)
+            };
+            serde_json::to_writer(&mut std, &value)?;
+            writeln!(std)?;
+        }
+        Commands::Status { all: true } => {
+            for subdirectory in directory.subdirectories(&read_options)? {
+                let path = &subdirectory.directory;
+                match subdirectory.name_status(&name_format) {
+                    Ok(status) => writeln!(std, "{path:?}: {status:?}")?,
+                    Err(e) => writeln!(std, "{path:?}: Failed to get status '{e}'")?,
+                }
+            }
+        }
+        Commands::Status { all: false } => match directory.name_intervals(&name_format) {
+            Ok((status, named, _)) => {
+                let status = match status {
+                    directory::NameStatus::Valid => "Date is valid",
+                    directory::NameStatus::Invalid => "Date is set but is invalid",
+                    directory::NameStatus::SuperSet => "Date is set but is superset",
+                    directory::NameStatus::None => "Date is not set",
+                };
+                writeln!(std, "{status}")?;
+                if let Some(named) = named.filter(|_| verbose) {
+                    writeln!(
+                        err,
+                        "Name interval from: {}, to: {}",
+                        named.from,
+                        named.to_date_only_display()
+                    )?;
+                }
+            }
             Err(e) => writeln!(std, "Failed to get status '{e}'")?,
         },
-        Commands::Rename {
+        cmd @ (Commands::Rename {
             max_interval,
-            dry_run,
-        } => {
-            let (status, new_path) = directory.rename(max_interval)?;
+            suffix_on_conflict,
+            ..
+        }
+        | Commands::RenameInterval {
+            max_interval,
+            suffix_on_conflict,
+            ..
+        }) => {
             use directory::NameStatus as NS;
-            match status {
-                NS::Valid => writeln!(err, "Directory already have right date")?,
-                NS::Invalid => writeln!(
-                    err,
-                    "Directory already have date, but it is not match content"
-                )?,
-                NS::SuperSet => writeln!(
-                    err,
-                    "Directories name is already super set of the right name"
-                )?,
+            let (status, new_path) = match cmd {
+                Commands::Rename { template, .. } => {
+                    directory.rename(max_interval, &name_format, template.as_ref())?
+                }
+                _ => {
+                    let new_path = directory.rename_interval(max_interval, &name_format)?;
+                    // Only the date is replaced, so any other name is renamed as if it had none
+                    let status = if new_path == directory.directory {
+                        NS::Valid
+                    } else {
+                        NS::None
+                    };
+                    (status, new_path)
+                }
+            };
+            let renamed = match status {
+                NS::Valid => {
+                    writeln!(err, "Directory already have right date")?;
+                    None
+                }
+                NS::Invalid => {
+                    writeln!(
+                        err,
+                        "Directory already have date, but it is not match content"
+                    )?;
+                    None
+                }
+                NS::SuperSet => {
+                    writeln!(
+                        err,
+                        "Directories name is already super set of the right name"
+                    )?;
+                    None
+                }
                 NS::None => {
+                    let new_path = match &name_policy {
+                        Some(policy) => policy.apply(new_path)?,
+                        None => new_path,
+                    };
+                    if new_path.exists() && !suffix_on_conflict {
+                        return Err(PhotoDaterError::TargetExists {
+                            from: directory.directory.clone(),
+                            to: new_path,
+                        }
+                        .into());
+                    }
+                    let new_path = directory::free_path(&new_path);
                     if !dry_run {
                         fs::rename(&directory.directory, &new_path)?;
+                        record(&directory.directory, &new_path)?;
                     }
-                    writeln!(std, "Rename {:?} to {:?}", directory.directory, new_path)?;
+                    if !dry_run_json {
+                        writeln!(std, "Rename {:?} to {:?}", directory.directory, new_path)?;
+                    }
+                    Some(new_path)
                 }
+            };
+            if dry_run_json {
+                let plan = renamed.iter().map(|to| (directory.directory.as_path(), to));
+                write_plan_json(&mut std, plan)?;
             }
+            let count = usize::from(renamed.is_some());
+            writeln!(
+                err,
+                "{} {count} {} ({} skipped)",
+                if dry_run { "Would rename" } else { "Renamed" },
+                if count == 1 {
+                    "directory"
+                } else {
+                    "directories"
+                },
+                1 - count
+            )?;
         }
-        Commands::List => directory
-            .get_files()
-            .get_sorted::<ByCreatedDate<&File>>()
-            .into_iter()
-            .try_for_each(|File { path, created }| writeln!(std, "{path:?}: Created {created}"))?,
-        Commands::Interval => match directory.get_files().interval() {
-            Some(interval) => writeln!(
-                std,
-                "from: {}, to: {} ({} days)",
-                interval.from,
-                interval.to,
-                interval.delta().num_days()
-            )?,
-            None => writeln!(err, "Not enaught files to check")?,
-        },
-        Commands::Check {
-            max_interval: max_days,
-        } => match directory
-            .get_files()
-            .interval()
-            .map(|interval| interval.delta())
-        {
-            Some(delta) if delta.abs().num_days() <= max_days.into() => {
-                writeln!(std, "OK")?;
+        Commands::List {
+            sort_by,
+            reverse,
+            with_source,
+            null,
+            absolute,
+        } => {
+            let mut files = directory
+                .get_files()
+                .get_sorted_by(|a, b| SortKey::compare_all(&sort_by, a, b));
+            if reverse {
+                files.reverse();
             }
-            Some(delta) => {
-                writeln!(err, "Delta is: {} days", delta.num_days())?;
+            let files = files
+                .into_iter()
+                .map(|file| {
+                    let mut file = file.clone();
+                    if absolute {
+                        file.path = absolute_path(&file.path, &mut err)?;
+                    }
+                    Ok(file)
+                })
+                .collect::<Result<Vec<_>>>()?;
+            match format {
+                OutputFormat::Text if null => files.iter().try_for_each(|file| {
+                    std.write_all(file.path.as_os_str().as_encoded_bytes())?;
+                    std.write_all(b"\0")
+                })?,
+                OutputFormat::Text => files.iter().try_for_each(|file| {
+                    write!(std, "{:?}: Created {}", file.path, file.created)?;
+                    if with_source {
+                        write!(std, " [{}]", file.source)?;
+                    }
+                    writeln!(std)
+                })?,
+                OutputFormat::Json => {
+                    let mut value = serde_json::to_value(&files)?;
+                    if let (true, Some(values)) = (with_source, value.as_array_mut()) {
+                        values.iter_mut().zip(&files).for_each(|(value, file)| {
+                            value["source"] = file.source.to_string().into();
+                        });
+                    }
+                    serde_json::to_writer(&mut std, &value)?;
+                    writeln!(std)?;
+                }
             }
-            None => {
-                writeln!(err, "There is no files to check for interval")?;
+        }
+        Commands::Interval {
+            unit,
+            per_day_counts,
+            nonzero_only,
+        } => {
+            let files = directory.get_files();
+            let day_counts = if per_day_counts {
+                files.counts_by_day_in_span()
+            } else {
+                Vec::new()
+            };
+            let day_counts = day_counts
+                .into_iter()
+                .filter(|(_, count)| !nonzero_only || *count > 0);
+            match (files.interval(), format) {
+                (Some(interval), OutputFormat::Text) => {
+                    writeln!(
+                        std,
+                        "from: {}, to: {} ({})",
+                        interval.from,
+                        interval.to,
+                        unit.format_delta(&interval)
+                    )?;
+                    day_counts
+                        .into_iter()
+                        .try_for_each(|(day, count)| writeln!(std, "{day}: {count}"))?;
+                }
+                (Some(interval), OutputFormat::Json) => {
+                    let mut value = serde_json::to_value(&interval)?;
+                    value["days"] = interval.delta_days().into();
+                    value[unit.name()] = match unit.delta(&interval) {
+                        Some(delta) => delta.into(),
+                        None => interval.iso_duration().into(),
+                    };
+                    if per_day_counts {
+                        value["per_day_counts"] = day_counts
+                            .map(|(day, count)| (day.to_string(), count.into()))
+                            .collect::<serde_json::Map<_, _>>()
+                            .into();
+                    }
+                    serde_json::to_writer(&mut std, &value)?;
+                    writeln!(std)?;
+                }
+                (None, _) => writeln!(err, "Not enaught files to check")?,
             }
-        },
+        }
+        Commands::Stats => {
+            let files = directory.get_files();
+            let days = files.interval().map_or(0, |interval| interval.delta_days());
+            writeln!(std, "Files: {}", files.len() + files.skipped().len())?;
+            writeln!(std, "With date: {}", files.len())?;
+            writeln!(std, "Span: {days} days")?;
+            writeln!(std, "Distinct days: {}", files.group_by_days().len())?;
+            files
+                .counts_by_month()
+                .into_iter()
+                .try_for_each(|(month, count)| {
+                    writeln!(
+                        std,
+                        "{month} {count:>6} {}",
+                        "#".repeat(count.min(HISTOGRAM_WIDTH))
+                    )
+                })?;
+        }
+        Commands::Count => {
+            let files = directory.get_files();
+            writeln!(std, "Files: {}", files.len())?;
+            let counts = files
+                .counts_by_extension()
+                .into_iter()
+                .map(|(extension, count)| format!("{extension}: {count}"))
+                .collect::<Vec<_>>();
+            if !counts.is_empty() {
+                writeln!(std, "{}", counts.join(", "))?;
+            }
+        }
+        Commands::DaySpans => {
+            directory
+                .get_files()
+                .day_spans()
+                .iter()
+                .try_for_each(|span| {
+                    let seconds = span.duration().num_seconds();
+                    writeln!(
+                        std,
+                        "{} {} - {} ({}:{:02}:{:02})",
+                        span.day,
+                        span.first.format("%H:%M:%S"),
+                        span.last.format("%H:%M:%S"),
+                        seconds / 3600,
+                        seconds / 60 % 60,
+                        seconds % 60
+                    )
+                })?;
+        }
+        Commands::Check {
+            max_interval,
+            min_interval,
+            unit,
+            quiet,
+        } => {
+            if let Some(min) = min_interval.filter(|min| *min > max_interval) {
+                return Err(anyhow!(
+                    "Minimum interval {min} is larger than the maximum interval {max_interval}"
+                ));
+            }
+            let interval = directory
+                .get_files()
+                .interval()
+                .ok_or(PhotoDaterError::NoFilesWithDates)?;
+            unit.check(&interval, min_interval, max_interval)?;
+            if !quiet {
+                writeln!(std, "OK")?;
+            }
+        }
         Commands::FilesRename {
-            dry_run,
             name,
             sort_by,
             digits,
             skip_date,
+            stable_index,
+            validate_order,
+            lowercase_ext,
+            scoped,
+            name_template,
+            slug,
+            reverse,
+            start,
+            ..
         } => {
             let files = directory.get_files();
-            let name = name.as_ref().map_or(directory.name()?, |n| n.as_str());
+            let name_template = name_template.or(config.name_template).unwrap_or_default();
+            let name_template = if slug {
+                name_template.slug()
+            } else {
+                name_template
+            };
+            let name = match &name {
+                Some(name) => name.as_str(),
+                None => directory.name()?,
+            };
             let name = if skip_date {
-                FilesInterval::try_split(name)
+                FilesInterval::try_split(name, &name_format)
                     .map(|(_interval, name)| name)
+                    // Names consisting only of the date are kept
+                    .filter(|name| !name.is_empty())
                     .unwrap_or(name)
             } else {
                 name
             };
-            match sort_by {
-                RenameFileSort::ByPath => files.rename_files::<ByPath<&File>>(name, digits),
-                RenameFileSort::ByCreatedDate => {
-                    files.rename_files::<ByCreatedDate<&File>>(name, digits)
-                }
-            }?
-            .into_iter()
-            .try_for_each(|RenamedFile(file, new_path)| {
-                if !dry_run {
-                    fs::rename(&file.path, &new_path)?;
+            let mut index = stable_index
+                .as_ref()
+                .map(StableIndex::read)
+                .transpose()?
+                .unwrap_or_default()
+                .with_start(start);
+            let groups;
+            let renamed = if scoped {
+                groups = files.group_by_parent();
+                groups
+                    .iter()
+                    .map(|(_parent, files)| {
+                        let mut index = StableIndex::default().with_start(start);
+                        sort_by.rename_files(
+                            files,
+                            name,
+                            digits,
+                            &name_template,
+                            &mut index,
+                            reverse,
+                        )
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .into_iter()
+                    .flatten()
+                    .collect()
+            } else {
+                sort_by.rename_files(files, name, digits, &name_template, &mut index, reverse)?
+            };
+            let renamed = if lowercase_ext {
+                files::lowercase_extensions(renamed)?
+            } else {
+                renamed
+            };
+            let (renamed, unchecked) = match &name_policy {
+                Some(policy) => policy.apply_all(renamed)?,
+                None => (renamed, Vec::new()),
+            };
+            unchecked.iter().try_for_each(|file| {
+                writeln!(
+                    err,
+                    "Skip {:?}, its new name is not valid UTF-8 and cannot be checked",
+                    file.path
+                )
+            })?;
+            if validate_order {
+                let breaks = files::order_breaks(&renamed);
+                breaks.iter().try_for_each(|(prev, next)| {
+                    writeln!(
+                        err,
+                        "{:?} sorts before {:?} but was created later",
+                        prev.1.file_name().unwrap_or_default(),
+                        next.1.file_name().unwrap_or_default(),
+                    )
+                })?;
+                if !breaks.is_empty() {
+                    return Err(anyhow!(
+                        "{} new names do not follow the creation date order",
+                        breaks.len()
+                    ));
                 }
-                writeln!(std, "Rename file {:?} => {:?}", file.path, new_path)?;
-                Ok::<(), anyhow::Error>(())
+                writeln!(std, "OK")?;
+                return Ok(());
+            }
+            #[cfg(feature = "ratatui")]
+            let renamed = if tui && !dry_run {
+                let renamed = tui::review(renamed)?;
+                // Rejected files keep their names, which approved ones may now collide with
+                files::check_collisions(&renamed)?;
+                renamed
+            } else {
+                renamed
+            };
+            if !dry_run {
+                Files::apply_renames(&renamed, &mut record)?;
+            }
+            if dry_run_json {
+                let plan = renamed
+                    .iter()
+                    .map(|RenamedFile(file, to)| (&*file.path, to));
+                write_plan_json(&mut std, plan)?;
+            } else {
+                renamed.iter().try_for_each(|RenamedFile(file, new_path)| {
+                    writeln!(std, "Rename file {:?} => {:?}", file.path, new_path)
+                })?;
+            }
+            if dry_run {
+                writeln!(err, "Summary: {}", PlanSummary::new(&renamed))?;
+            }
+            let mut count = PlanCount::new(&renamed);
+            count.skipped += unchecked.len();
+            writeln!(err, "{}", count.describe(("rename", "Renamed"), dry_run))?;
+            if let (Some(path), false) = (&stable_index, dry_run) {
+                index.rename(
+                    renamed
+                        .iter()
+                        .map(|RenamedFile(file, new_path)| (&*file.path, new_path)),
+                );
+                index.write(path)?;
+            }
+        }
+        Commands::ClockCheck => {
+            let jumps = directory.get_files().clock_jumps();
+            jumps.iter().try_for_each(|(prev, next)| {
+                writeln!(
+                    std,
+                    "{:?} ({}) => {:?} ({})",
+                    prev.path, prev.created, next.path, next.created
+                )
             })?;
+            if jumps.is_empty() {
+                writeln!(std, "OK")?;
+            }
         }
-        Commands::MoveByDays { dry_run } => directory
-            .get_files()
-            .move_by_days()
-            .into_iter()
-            .flatten()
-            .try_for_each(|RenamedFile(file, new_path)| {
-                if let Some(parent) = &new_path.parent() {
+        Commands::Verify { max_drift_days } => {
+            let drifted = directory
+                .get_files()
+                .drifted(TimeDelta::days(max_drift_days.into()))?;
+            drifted.iter().try_for_each(|(file, modified)| {
+                writeln!(
+                    std,
+                    "{:?} exif {} filesystem {}",
+                    file.path, file.created, modified
+                )
+            })?;
+            if drifted.is_empty() {
+                writeln!(std, "OK")?;
+            }
+        }
+        Commands::Dedupe { delete } => {
+            let mut removed = 0;
+            for set in directory.get_files().duplicates()? {
+                let paths = set.iter().map(|file| &file.path).collect::<Vec<_>>();
+                writeln!(std, "Duplicates {paths:?}")?;
+                if delete {
                     if !dry_run {
-                        fs::create_dir_all(parent)?;
-                        fs::rename(&file.path, &new_path)?;
+                        set[1..]
+                            .iter()
+                            .try_for_each(|file| fs::remove_file(&file.path))?;
                     }
-                    writeln!(std, "Move file {:?} => {:?}", file.path, new_path)?;
+                    removed += set.len() - 1;
                 }
-                Ok::<(), anyhow::Error>(())
-            })?,
-    }
-    Ok(())
+            }
+            if delete {
+                let verb = if dry_run { "Would remove" } else { "Removed" };
+                writeln!(err, "{verb} {removed} duplicate files")?;
+            }
+        }
+        Commands::Tree {
+            names,
+            flatten,
+            out,
+        } => {
+            let files = directory.get_files();
+            let plan = match &out {
+                Some(out) => files.move_by_days_flatten(out),
+                None if flatten => files.move_by_days_flatten(&directory.directory),
+                None => files.move_by_days(),
+            };
+            let plan = plan.into_iter().flatten().collect::<Vec<_>>();
+            let root = out.as_deref().unwrap_or(&directory.directory);
+            write!(std, "{}", tree::render_plan(&plan, root, names))?;
+        }
+        Commands::Calendar { year } => write!(
+            std,
+            "{}",
+            calendar::render_year(year, &directory.get_files().counts_by_day())
+        )?,
+        cmd @ (Commands::MoveByDays { verify_after, .. }
+        | Commands::MoveByMonth { verify_after, .. }
+        | Commands::MoveByLocation { verify_after, .. }
+        | Commands::MoveByDateTree { verify_after, .. }) => {
+            let files = directory.get_files();
+            let plan = match &cmd {
+                Commands::MoveByMonth { .. } => files.move_by_month(),
+                Commands::MoveByLocation { decimals, .. } => files.move_by_location(*decimals)?,
+                Commands::MoveByDateTree { levels, .. } => files.move_by_date_tree(*levels),
+                Commands::MoveByDays { out: Some(out), .. } => files.move_by_days_flatten(out),
+                Commands::MoveByDays { flatten: true, .. } => {
+                    files.move_by_days_flatten(&directory.directory)
+                }
+                _ => files.move_by_days(),
+            };
+            let unplanned = files.unplanned(&plan);
+            unplanned.iter().try_for_each(|file| {
+                writeln!(
+                    err,
+                    "Cannot move {:?}, it has no parent directory or file name",
+                    file.path
+                )
+            })?;
+            let (plan, duplicates) = match cmd {
+                Commands::MoveByDays {
+                    dedupe_content: true,
+                    ..
+                } => files::dedupe_content(plan)?,
+                _ => (plan, Vec::new()),
+            };
+            duplicates.iter().try_for_each(|(duplicate, original)| {
+                writeln!(
+                    err,
+                    "Skip duplicate {:?} of {:?}",
+                    duplicate.path, original.path
+                )
+            })?;
+            let plan = plan.into_iter().flatten().collect::<Vec<_>>();
+            #[cfg(feature = "ratatui")]
+            let plan = if tui && !dry_run {
+                tui::review(plan)?
+            } else {
+                plan
+            };
+            if !dry_run {
+                // Moved files keep their names, which other files may already have
+                files::check_collisions(&plan)?;
+                Files::apply_renames(&plan, &mut record)?;
+            }
+            if dry_run_json {
+                let plan = plan.iter().map(|RenamedFile(file, to)| (&*file.path, to));
+                write_plan_json(&mut std, plan)?;
+            } else {
+                plan.iter().try_for_each(|RenamedFile(file, new_path)| {
+                    writeln!(std, "Move file {:?} => {:?}", file.path, new_path)
+                })?;
+            }
+            if dry_run {
+                writeln!(err, "Summary: {}", PlanSummary::new(&plan))?;
+            }
+            let mut count = PlanCount::new(&plan);
+            count.skipped += duplicates.len() + unplanned.len();
+            writeln!(err, "{}", count.describe(("move", "Moved"), dry_run))?;
+            if verify_after && !dry_run {
+                let discrepancies = Files::verify_moves(&plan, &read_options);
+                discrepancies
+                    .iter()
+                    .try_for_each(|d| writeln!(err, "Verification failed: {d}"))?;
+                if !discrepancies.is_empty() {
+                    return Err(anyhow!(
+                        "{} of {} moved files failed verification",
+                        discrepancies.len(),
+                        plan.len()
+                    ));
+                }
+                writeln!(err, "Verified {} moved files", plan.len())?;
+            }
+        }
+        Commands::PruneEmpty => {
+            directory
+                .empty_date_dirs(&name_format)?
+                .iter()
+                .try_for_each(|path| {
+                    if !dry_run {
+                        directory::remove_empty_dir(path)?;
+                    }
+                    writeln!(std, "Remove empty directory {path:?}")
+                })?;
+        }
+        Commands::SuggestMerge { max_gap } => {
+            directory
+                .suggest_merges(max_gap, &name_format)?
+                .iter()
+                .try_for_each(|(paths, interval)| {
+                    let names = paths
+                        .iter()
+                        .filter_map(|path| path.file_name())
+                        .collect::<Vec<_>>();
+                    writeln!(
+                        std,
+                        "Merge {names:?} => {:?}",
+                        interval.format(&name_format)
+                    )
+                })?;
+        }
+        // Handled before reading the directory
+        Commands::Apply { .. } | Commands::Undo { .. } => {}
+    }
+    if let (Some(marker), Some(interval), false) =
+        (&marker, directory.get_files().interval(), dry_run)
+    {
+        last_run::write(marker, interval.to)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::write_photo;
+
+    /// Runs the CLI with the given arguments, returning captured stdout and stderr.
+    fn run_cli(args: &[&str]) -> Result<(String, String)> {
+        let mut std = Vec::new();
+        let mut err = Vec::new();
+        run(["photo-dater"].iter().chain(args), &mut std, &mut err)?;
+        Ok((String::from_utf8(std)?, String::from_utf8(err)?))
+    }
+
+    /// Extracts file names from the quoted paths printed by `list`.
+    fn listed_names(output: &str) -> Vec<String> {
+        output
+            .lines()
+            .filter_map(|line| line.split('"').nth(1))
+            .filter_map(|path| Some(PathBuf::from(path).file_name()?.to_str()?.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn calendar() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:01 14:15:16");
+
+        let (std, _) = run_cli(&[dir.path().to_str().unwrap(), "calendar", "2025"])?;
+        let may = std.split("2025-05\n").nth(1).unwrap();
+        assert_eq!(may.lines().nth(1), Some("               2   .   .   ."));
+        Ok(())
+    }
+
+    #[test]
+    fn list_sort_by_multiple_keys() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("c.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b.jpg"), "2025:04:30 12:13:14");
+        let dir = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir, "list", "--sort-by", "created,path"])?;
+        assert_eq!(listed_names(&std), ["b.jpg", "a.jpg", "c.jpg"]);
+
+        let (std, _) = run_cli(&[dir, "list", "--sort-by", "path,created"])?;
+        assert_eq!(listed_names(&std), ["a.jpg", "b.jpg", "c.jpg"]);
+        Ok(())
+    }
+
+    #[test]
+    fn reverse() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b.jpg"), "2025:05:03 12:13:14");
+        write_photo(dir.path().join("c.jpg"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "list", "--reverse"])?;
+        assert_eq!(listed_names(&std), ["b.jpg", "c.jpg", "a.jpg"]);
+
+        run_cli(&[
+            dir_str,
+            "files-rename",
+            "--name",
+            "p",
+            "--sort-by",
+            "by-created-date",
+            "--reverse",
+        ])?;
+        let (std, _) = run_cli(&[dir_str, "list"])?;
+        assert_eq!(listed_names(&std), ["p 3.jpg", "p 2.jpg", "p 1.jpg"]);
+        Ok(())
+    }
+
+    #[test]
+    fn list_sort_by_rejects_unknown_key() {
+        assert!(Cli::try_parse_from(["photo-dater", "list", "--sort-by", "created,size"]).is_err());
+        assert_eq!(
+            Cli::try_parse_from(["photo-dater", "list", "--sort-by", "created,by-path"])
+                .unwrap()
+                .cmd,
+            Commands::List {
+                sort_by: vec![SortKey::ByCreatedDate, SortKey::ByPath],
+                reverse: false,
+                with_source: false,
+                null: false,
+                absolute: false,
+            }
+        );
+    }
+
+    #[test]
+    fn list_absolute() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("sub/1.jpg"), "2025:05:01 12:13:14");
+        let canonical = fs::canonicalize(dir.path().join("sub/1.jpg"))?;
+        let dir_str = dir.path().join("sub/..").to_str().unwrap().to_string();
+
+        let (std, _) = run_cli(&[&dir_str, "list", "--absolute", "-0"])?;
+        assert_eq!(std, format!("{}\0", canonical.to_str().unwrap()));
+
+        let (std, _) = run_cli(&[&dir_str, "--format", "json", "list", "--absolute"])?;
+        let listed = serde_json::from_str::<serde_json::Value>(&std)?;
+        assert_eq!(listed[0]["path"], canonical.to_str().unwrap());
+
+        assert_eq!(absolute_path(&canonical, io::sink())?, canonical);
+        let mut err = Vec::new();
+        let missing = dir.path().join("missing.jpg");
+        assert_eq!(absolute_path(&missing, &mut err)?, missing);
+        assert!(String::from_utf8(err)?.starts_with("Cannot canonicalize"));
+        Ok(())
+    }
+
+    #[test]
+    fn error_log() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let log = dir.path().join("errors.log");
+
+        let (_, err) = run_cli(&[
+            dir.path().to_str().unwrap(),
+            "interval",
+            "--error-log",
+            log.to_str().unwrap(),
+        ])?;
+        assert_eq!(err, "Not enaught files to check\n");
+        assert_eq!(fs::read_to_string(&log)?, err);
+
+        let missing = dir.path().join("missing");
+        let result = run_cli(&[
+            missing.to_str().unwrap(),
+            "list",
+            "--error-log",
+            log.to_str().unwrap(),
+        ]);
+        let e = result.unwrap_err();
+        assert_eq!(fs::read_to_string(&log)?, format!("{err}Error: {e:?}\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn interval_unit() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 10:00:00");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 13:00:00");
+        let dir_str = dir.path().to_str().unwrap();
+        let interval = |args: &[&str]| -> Result<String> {
+            Ok(run_cli(&[&[dir_str, "interval"], args].concat())?.0)
+        };
+
+        let from_to = "from: 2025-05-01 10:00:00, to: 2025-05-03 13:00:00";
+        assert_eq!(interval(&[])?, format!("{from_to} (2 days)\n"));
+        assert_eq!(
+            interval(&["--unit", "days"])?,
+            format!("{from_to} (2 days)\n")
+        );
+        assert_eq!(
+            interval(&["--unit", "hours"])?,
+            format!("{from_to} (51 hours)\n")
+        );
+        assert_eq!(interval(&["-u", "iso"])?, format!("{from_to} (P2DT3H)\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&interval(&["-u", "iso", "--format", "json"])?)?;
+        assert_eq!(json["days"], 2);
+        assert_eq!(json["duration"], "P2DT3H");
+        let json: serde_json::Value =
+            serde_json::from_str(&interval(&["-u", "hours", "--format", "json"])?)?;
+        assert_eq!(json["hours"], 51);
+        Ok(())
+    }
+
+    #[test]
+    fn interval_per_day_counts() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 10:00:00");
+        write_photo(dir.path().join("2.jpg"), "2025:05:01 11:00:00");
+        write_photo(dir.path().join("3.jpg"), "2025:05:04 13:00:00");
+        let dir_str = dir.path().to_str().unwrap();
+        let interval = |args: &[&str]| -> Result<String> {
+            Ok(run_cli(&[&[dir_str, "interval", "--per-day-counts"], args].concat())?.0)
+        };
+
+        assert_eq!(
+            interval(&[])?,
+            "from: 2025-05-01 10:00:00, to: 2025-05-04 13:00:00 (3 days)\n\
+             2025-05-01: 2\n2025-05-02: 0\n2025-05-03: 0\n2025-05-04: 1\n"
+        );
+        assert!(interval(&["--nonzero-only"])?.ends_with("\n2025-05-01: 2\n2025-05-04: 1\n"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&interval(&["--nonzero-only", "--format", "json"])?)?;
+        assert_eq!(
+            json["per_day_counts"],
+            serde_json::json!({"2025-05-01": 2, "2025-05-04": 1})
+        );
+        assert!(Cli::try_parse_from(["photo-dater", "interval", "--nonzero-only"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn interval_end_of_day() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 23:59:59");
+
+        // The time of the last photo is real, not the end of a day recognized from a name
+        let (std, _) = run_cli(&[dir.path().to_str().unwrap(), "interval"])?;
+        assert_eq!(
+            std,
+            "from: 2025-05-01 12:13:14, to: 2025-05-03 23:59:59 (2 days)\n"
+        );
+
+        // Names of intervals recognized from directory names show no time
+        let named = dir.path().join("2025-05-01 - 03 Trip");
+        write_photo(named.join("1.jpg"), "2025:05:02 12:13:14");
+        let (std, _) = run_cli(&[named.to_str().unwrap(), "status", "--format", "json"])?;
+        assert!(std.contains("\"name_interval\":\"2025-05-01 - 03\""));
+        assert!(!std.contains("23:59:59"));
+        let (_, err) = run_cli(&[named.to_str().unwrap(), "--verbose", "status"])?;
+        assert_eq!(
+            err,
+            "Name interval from: 2025-05-01 00:00:00, to: 2025-05-03\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn check() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dir_str = dir.path().to_str().unwrap();
+
+        let err = run_cli(&[dir_str, "check", "3"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::NoFilesWithDates)
+        ));
+
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 14:15:16");
+        let (std, _) = run_cli(&[dir_str, "check", "3"])?;
+        assert_eq!(std, "OK\n");
+        let (std, _) = run_cli(&[dir_str, "check", "3", "--quiet"])?;
+        assert_eq!(std, "");
+
+        let err = run_cli(&[dir_str, "check", "1", "--quiet"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::IntervalTooLarge { days: 2, .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn check_min_interval() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dir_str = dir.path().to_str().unwrap();
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 14:15:16");
+
+        // Within range
+        let (std, _) = run_cli(&[dir_str, "check", "3", "--min-interval", "2"])?;
+        assert_eq!(std, "OK\n");
+
+        // Below minimum
+        let err = run_cli(&[dir_str, "check", "7", "--min-interval", "5"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::IntervalTooSmall {
+                days: 2,
+                min: 5,
+                ..
+            })
+        ));
+        assert!(err
+            .to_string()
+            .ends_with("(2 days, 3 under the minimum of 5)"));
+        let args = [
+            dir_str,
+            "check",
+            "72",
+            "-u",
+            "hours",
+            "--min-interval",
+            "60",
+        ];
+        let err = run_cli(&args).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("(50 hours, 10 under the minimum of 60)"));
+
+        // Above maximum
+        let err = run_cli(&[dir_str, "check", "1", "--min-interval", "1"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("(2 days, 1 over the maximum of 1)"));
+
+        let err = run_cli(&[dir_str, "check", "1", "--min-interval", "2"]).unwrap_err();
+        assert!(err.to_string().contains("larger than the maximum"));
+        Ok(())
+    }
+
+    #[test]
+    fn check_hours() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dir_str = dir.path().to_str().unwrap();
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 10:00:00");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 09:30:00");
+
+        // 47 hours and 30 minutes are rounded down to 1 day
+        let (std, _) = run_cli(&[dir_str, "check", "1"])?;
+        assert_eq!(std, "OK\n");
+        let (std, _) = run_cli(&[dir_str, "check", "47", "--unit", "hours"])?;
+        assert_eq!(std, "OK\n");
+
+        let err = run_cli(&[dir_str, "check", "24", "--unit", "hours"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::IntervalTooLargeHours { hours: 47, .. })
+        ));
+        assert!(err
+            .to_string()
+            .ends_with("(47 hours, 23 over the maximum of 24)"));
+
+        // Durations are only printed by `interval`
+        assert!(run_cli(&[dir_str, "check", "1", "--unit", "iso"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn typed_errors() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("1.jpg");
+        write_photo(&file, "2025:05:01 12:13:14");
+
+        let err = run_cli(&[file.to_str().unwrap(), "list"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::NotADirectory(path)) if path == &file
+        ));
+
+        let empty = dir.path().join("empty");
+        fs::create_dir(&empty)?;
+        let err = run_cli(&[empty.to_str().unwrap(), "rename"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::NoFilesWithDates)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn assume_utc_requires_timezone() {
+        assert!(Cli::try_parse_from(["photo-dater", "list", "--assume-utc"]).is_err());
+        assert!(
+            Cli::try_parse_from(["photo-dater", "list", "--to-timezone", "Europe/Prague"]).is_err()
+        );
+        assert!(Cli::try_parse_from([
+            "photo-dater",
+            "list",
+            "--assume-utc",
+            "--to-timezone",
+            "Europe/Prague"
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn camera_timezone() -> Result<()> {
+        let day_of = |tz: &str| -> Result<Vec<String>> {
+            let dir = tempfile::tempdir()?;
+            write_photo(dir.path().join("1.jpg"), "2025:05:01 23:30:00");
+            let dir = dir.path().to_str().unwrap();
+            run_cli(&[
+                dir,
+                "move-by-days",
+                "--tz",
+                tz,
+                "--to-timezone",
+                "Europe/Prague",
+            ])?;
+            let mut days = fs::read_dir(dir)?
+                .map(|entry| Ok(entry?.file_name().to_string_lossy().into_owned()))
+                .collect::<Result<Vec<_>>>()?;
+            days.sort();
+            Ok(days)
+        };
+
+        // 23:30 in New York is 05:30 of the next day in Prague
+        assert_eq!(day_of("America/New_York")?, vec!["2025-05-02"]);
+        // 23:30 in Tokyo is 16:30 of the same day in Prague
+        assert_eq!(day_of("Asia/Tokyo")?, vec!["2025-05-01"]);
+
+        assert!(Cli::try_parse_from([
+            "photo-dater",
+            "list",
+            "--tz",
+            "Asia/Tokyo",
+            "--assume-utc",
+            "--to-timezone",
+            "Europe/Prague"
+        ])
+        .is_err());
+        assert!(Cli::try_parse_from(["photo-dater", "list", "--timezone", "Asia/Tokyo"]).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn verbose_skipped_files() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        fs::write(dir.path().join("notes.txt"), "no exif here")?;
+        let dir = dir.path().to_str().unwrap();
+
+        let (_, err) = run_cli(&[dir, "list"])?;
+        assert_eq!(err, "");
+        let (_, err) = run_cli(&[dir, "list", "--verbose"])?;
+        assert_eq!(err, "Skipped 1 files without dates\n");
+        let (_, err) = run_cli(&[dir, "--verbose", "status"])?;
+        assert_eq!(err, "Skipped 1 files without dates\n");
+        Ok(())
+    }
+
+    #[test]
+    fn since_last() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "list", "--since-last"])?;
+        assert_eq!(listed_names(&std), ["1.jpg", "2.jpg"]);
+
+        write_photo(dir.path().join("3.jpg"), "2025:05:03 12:13:14");
+        let (std, _) = run_cli(&[dir_str, "list", "--since-last"])?;
+        assert_eq!(listed_names(&std), ["3.jpg"]);
+
+        let (std, _) = run_cli(&[dir_str, "list", "--since-last"])?;
+        assert_eq!(listed_names(&std), Vec::<String>::new());
+
+        let (std, _) = run_cli(&[dir_str, "list"])?;
+        assert_eq!(listed_names(&std), ["1.jpg", "2.jpg", "3.jpg"]);
+        Ok(())
+    }
+
+    #[test]
+    fn since_until() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join("3.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "list", "--since", "2025-05-02"])?;
+        assert_eq!(listed_names(&std), ["2.jpg", "3.jpg"]);
+
+        let (std, _) = run_cli(&[dir_str, "--until", "2025-05-02", "list"])?;
+        assert_eq!(listed_names(&std), ["1.jpg", "2.jpg"]);
+
+        let (std, _) = run_cli(&[
+            dir_str,
+            "interval",
+            "--since",
+            "2025-05-02",
+            "--until",
+            "2025-05-02",
+        ])?;
+        assert_eq!(
+            std,
+            "from: 2025-05-02 12:13:14, to: 2025-05-02 12:13:14 (0 days)\n"
+        );
+
+        let err = run_cli(&[dir_str, "interval", "--since", "2026-01-01"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::NoFilesInRange)
+        ));
+        assert!(Cli::try_parse_from(["photo-dater", "list", "--since", "May"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn extra_dirs() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        write_photo(root.path().join("a/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(root.path().join("b/2.jpg"), "2025:05:03 12:13:14");
+        write_photo(root.path().join("c/3.jpg"), "2025:05:02 12:13:14");
+        let path = |name: &str| root.path().join(name).to_str().unwrap().to_string();
+
+        let (std, _) = run_cli(&[&path("a"), "interval", "--extra-dir", &path("b")])?;
+        assert_eq!(
+            std,
+            "from: 2025-05-01 12:13:14, to: 2025-05-03 12:13:14 (2 days)\n"
+        );
+
+        let (std, _) = run_cli(&[
+            &path("a"),
+            "--extra-dir",
+            &path("b"),
+            "--extra-dir",
+            &path("c"),
+            "list",
+        ])?;
+        assert_eq!(listed_names(&std), ["1.jpg", "3.jpg", "2.jpg"]);
+
+        let err = run_cli(&[&path("a"), "--extra-dir", &path("b"), "move-by-days"]).unwrap_err();
+        assert!(err.to_string().contains("single directory"));
+        assert!(root.path().join("a/1.jpg").exists());
+
+        let err = run_cli(&[&path("a"), "--extra-dir", &path("missing"), "list"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::NotADirectory(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn custom_date_format() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("Trip");
+        fs::create_dir(&dir)?;
+        write_photo(dir.join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.join("2.jpg"), "2025:05:03 14:15:16");
+        let args = ["--date-separator", ".", "--range-separator", "_"];
+
+        run_cli(&[&[dir.to_str().unwrap(), "rename", "5"], &args[..]].concat())?;
+        let dir = root.path().join("2025.05.01_03 Trip");
+        assert!(dir.is_dir());
+
+        let (std, _) = run_cli(&[&[dir.to_str().unwrap(), "status"], &args[..]].concat())?;
+        assert_eq!(std, "Date is valid\n");
+        let (std, _) = run_cli(&[dir.to_str().unwrap(), "status"])?;
+        assert_eq!(std, "Date is not set\n");
+        Ok(())
+    }
+
+    #[test]
+    fn rename_interval() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("2025-05-01 Trip");
+        write_photo(dir.join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.join("2.jpg"), "2025:05:03 12:13:14");
+
+        let (_, err) = run_cli(&[dir.to_str().unwrap(), "rename", "2"])?;
+        assert!(err.starts_with("Directory already have date, but it is not match content\n"));
+        assert!(dir.is_dir());
+
+        let (std, _) = run_cli(&[dir.to_str().unwrap(), "rename-interval", "2"])?;
+        let renamed = root.path().join("2025-05-01 - 03 Trip");
+        assert_eq!(std, format!("Rename {dir:?} to {renamed:?}\n"));
+        assert!(renamed.join("2.jpg").is_file());
+
+        let (_, err) = run_cli(&[renamed.to_str().unwrap(), "rename-interval", "2"])?;
+        assert!(err.starts_with("Directory already have right date\n"));
+
+        // Reported as correct by `status`, so left alone
+        let inner = root.path().join("Trip 2025-05-01 - 03 Paris");
+        fs::rename(&renamed, &inner)?;
+        let (std, err) = run_cli(&[inner.to_str().unwrap(), "rename-interval", "2"])?;
+        assert_eq!(std, "");
+        assert!(err.starts_with("Directory already have right date\n"));
+        assert!(inner.is_dir());
+        Ok(())
+    }
+
+    #[test]
+    fn rename_template() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("Trip");
+        write_photo(dir.join("1.jpg"), "2025:05:01 12:13:14");
+
+        let (std, _) = run_cli(&[
+            dir.to_str().unwrap(),
+            "rename",
+            "--template",
+            "{name} [{interval}]",
+        ])?;
+        let renamed = root.path().join("Trip [2025-05-01]");
+        assert_eq!(std, format!("Rename {dir:?} to {renamed:?}\n"));
+        assert!(renamed.join("1.jpg").is_file());
+
+        let (_, err) = run_cli(&[
+            renamed.to_str().unwrap(),
+            "rename",
+            "--template",
+            "{name} [{interval}]",
+        ])?;
+        assert!(err.starts_with("Directory already have right date\n"));
+
+        let err = Cli::try_parse_from(["photo-dater", "rename", "--template", "{name} {date}"])
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown placeholder {date}"));
+        Ok(())
+    }
+
+    #[test]
+    fn files_from() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        write_photo(path("a/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("b/2.jpg"), "2025:05:03 12:13:14");
+        write_photo(path("b/3.jpg"), "2025:05:09 12:13:14");
+        let list = path("list.txt");
+        fs::write(
+            &list,
+            format!(
+                "{}\n{}\n",
+                path("b/2.jpg").display(),
+                path("a/1.jpg").display()
+            ),
+        )?;
+        let list = list.to_str().unwrap();
+
+        let (std, _) = run_cli(&["--files-from", list, "interval"])?;
+        assert_eq!(
+            std,
+            "from: 2025-05-01 12:13:14, to: 2025-05-03 12:13:14 (2 days)\n"
+        );
+
+        let err = run_cli(&["--files-from", list, "rename"]).unwrap_err();
+        assert!(err.to_string().contains("cannot be used with --files-from"));
+
+        // Listed files are renamed and moved where they are
+        run_cli(&["--files-from", list, "files-rename", "-n", "p"])?;
+        assert!(path("a/p 1.jpg").is_file());
+        assert!(path("b/p 2.jpg").is_file());
+        assert!(path("b/3.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn files_from_stdin() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        write_photo(path("a/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("b/2.jpg"), "2025:05:03 12:13:14");
+        write_photo(path("b/3.jpg"), "2025:05:09 12:13:14");
+        let list = format!(
+            "{}\n{}\n",
+            path("a/1.jpg").display(),
+            path("b/2.jpg").display()
+        );
+
+        let (mut std, mut err) = (Vec::new(), Vec::new());
+        let cli = Cli::parse_from(["photo-dater", "--files-from", "-", "move-by-days"]);
+        execute(cli, list.as_bytes(), &mut std, &mut err)?;
+        assert!(path("a/2025-05-01/1.jpg").is_file());
+        assert!(path("b/2025-05-03/2.jpg").is_file());
+        assert!(path("b/3.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn status_all() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let path = |name: &str| root.path().join(name);
+        write_photo(path("2025-05-01 Trip/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("2025-05-03 Party/2.jpg"), "2025:05:04 12:13:14");
+        write_photo(path("Hike/3.jpg"), "2025:05:05 12:13:14");
+        write_photo(path("Hike/day 2/4.jpg"), "2025:05:06 12:13:14");
+        fs::create_dir(path("empty"))?;
+
+        let (std, _) = run_cli(&[root.path().to_str().unwrap(), "status", "--all"])?;
+        assert_eq!(
+            std,
+            format!(
+                "{:?}: Valid\n{:?}: Invalid\n{:?}: None\n{:?}: Failed to get status '{}'\n",
+                path("2025-05-01 Trip"),
+                path("2025-05-03 Party"),
+                path("Hike"),
+                path("empty"),
+                PhotoDaterError::NoFilesWithDates
+            )
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn status_json() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let path = |name: &str| root.path().join(name);
+        write_photo(path("2025-05-01 Trip/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("2025-05-01 Trip/2.jpg"), "2025:05:02 12:13:14");
+        // Renames the only directory and checks its status
+        let status = |name: &str| -> Result<serde_json::Value> {
+            let renamed = path(name);
+            fs::rename(root.path().read_dir()?.next().unwrap()?.path(), &renamed)?;
+            let (std, _) = run_cli(&[renamed.to_str().unwrap(), "status", "--format", "json"])?;
+            Ok(serde_json::from_str(&std)?)
+        };
+        let json = |name: &str, status: &str, name_interval: Option<&str>| {
+            serde_json::json!({
+                "directory": path(name).to_str().unwrap(),
+                "status": status,
+                "name_interval": name_interval,
+                "files_interval": "2025-05-01 - 02",
+            })
+        };
+
+        assert_eq!(
+            status("2025-05-01 - 02 Trip")?,
+            json("2025-05-01 - 02 Trip", "Valid", Some("2025-05-01 - 02"))
+        );
+        assert_eq!(
+            status("2025-05-01 Trip")?,
+            json("2025-05-01 Trip", "Invalid", Some("2025-05-01"))
+        );
+        assert_eq!(
+            status("2025-04-30 - 05-03 Trip")?,
+            json(
+                "2025-04-30 - 05-03 Trip",
+                "SuperSet",
+                Some("2025-04-30 - 05-03")
+            )
+        );
+        assert_eq!(status("Trip")?, json("Trip", "None", None));
+
+        let (std, _) = run_cli(&[
+            root.path().to_str().unwrap(),
+            "status",
+            "--all",
+            "--format",
+            "json",
+        ])?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&std)?,
+            serde_json::json!([json("Trip", "None", None)])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rename_target_exists() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("Trip");
+        write_photo(dir.join("1.jpg"), "2025:05:01 12:13:14");
+        fs::create_dir(root.path().join("2025-05-01 Trip"))?;
+        let dir_str = dir.to_str().unwrap();
+
+        let err = run_cli(&[dir_str, "rename"]).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(PhotoDaterError::TargetExists { .. })
+        ));
+        assert!(dir.join("1.jpg").is_file());
+
+        run_cli(&[dir_str, "rename", "--suffix-on-conflict"])?;
+        assert!(root.path().join("2025-05-01 Trip (1)/1.jpg").is_file());
+        assert!(!dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn rename_with_weekday() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("Trip");
+        fs::create_dir(&dir)?;
+        write_photo(dir.join("1.jpg"), "2025:05:01 12:13:14");
+
+        run_cli(&[dir.to_str().unwrap(), "--with-weekday", "rename"])?;
+        let dir = root.path().join("2025-05-01 (Thursday) Trip");
+        assert!(dir.is_dir());
+
+        let (std, _) = run_cli(&[dir.to_str().unwrap(), "--with-weekday", "status"])?;
+        assert_eq!(std, "Date is valid\n");
+        let (std, _) = run_cli(&[
+            dir.to_str().unwrap(),
+            "--with-weekday",
+            "files-rename",
+            "--skip-date",
+            "--dry-run",
+        ])?;
+        assert!(std.contains("Trip 1.jpg"));
+        assert!(!std.contains("(Thursday) Trip 1.jpg"));
+        Ok(())
+    }
+
+    #[test]
+    fn json_format() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 14:15:16");
+        let dir = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir, "list", "--format", "json"])?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&std)?,
+            serde_json::json!([
+                {"path": format!("{dir}/1.jpg"), "created": "2025-05-01T12:13:14"},
+                {"path": format!("{dir}/2.jpg"), "created": "2025-05-03T14:15:16"},
+            ])
+        );
+
+        let (std, _) = run_cli(&[dir, "--format", "json", "interval"])?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&std)?,
+            serde_json::json!({"from": "2025-05-01T12:13:14", "to": "2025-05-03T14:15:16", "days": 2})
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn list_with_source() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        fs::write(dir.path().join("IMG-20250502-WA0001.jpg"), "no exif here")?;
+        let dir = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir, "--name-dates", "list", "--with-source"])?;
+        assert_eq!(
+            std.lines()
+                .map(|line| line.rsplit(' ').next().unwrap())
+                .collect::<Vec<_>>(),
+            ["[exif]", "[filename]"]
+        );
+
+        let (std, _) = run_cli(&[dir, "--name-dates", "list"])?;
+        assert!(!std.contains("[exif]"));
+
+        let (std, _) = run_cli(&[
+            dir,
+            "--name-dates",
+            "--format",
+            "json",
+            "list",
+            "--with-source",
+        ])?;
+        let value = serde_json::from_str::<serde_json::Value>(&std)?;
+        assert_eq!(value[0]["source"], "exif");
+        assert_eq!(value[1]["source"], "filename");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn list_null_delimited() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1 with space.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2\nnewline.jpg"), "2025:05:02 12:13:14");
+        let dir = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir, "list", "-0"])?;
+        assert_eq!(
+            std,
+            format!("{dir}/1 with space.jpg\0{dir}/2\nnewline.jpg\0")
+        );
+        assert!(Cli::try_parse_from(["photo-dater", "list", "--null", "--with-source"]).is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn json_format_non_utf8_path() -> Result<()> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let dir = tempfile::tempdir()?;
+        write_photo(
+            dir.path().join(OsStr::from_bytes(b"invalid \xFF.jpg")),
+            "2025:05:01 12:13:14",
+        );
+        assert!(run_cli(&[dir.path().to_str().unwrap(), "list", "--format", "json"]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_validate_order() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        (1..=10).for_each(|i| {
+            write_photo(
+                dir.path().join(format!("{i:02}.jpg")),
+                &format!("2025:05:01 12:00:{i:02}"),
+            )
+        });
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, err) = run_cli(&[dir_str, "files-rename", "-n", "p", "--validate-order"])?;
+        assert_eq!((std.as_str(), err.as_str()), ("OK\n", ""));
+
+        // Numbered first by path, but taken last
+        write_photo(dir.path().join("00.jpg"), "2025:05:01 13:00:00");
+        let (mut std, mut err) = (Vec::new(), Vec::new());
+        let args = [
+            "photo-dater",
+            dir_str,
+            "files-rename",
+            "-n",
+            "p",
+            "--validate-order",
+        ];
+        let result = run(args, &mut std, &mut err);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "1 new names do not follow the creation date order"
+        );
+        assert_eq!(std, b"");
+        assert_eq!(
+            String::from_utf8(err)?,
+            "\"p 01.jpg\" sorts before \"p 02.jpg\" but was created later\n"
+        );
+        assert!(dir.path().join("01.jpg").exists());
+
+        let err = run_cli(&[dir_str, "files-rename", "-n", "p", "--digits", "1"]).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("1 digits are not enough for number 11"));
+        assert!(dir.path().join("00.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_target_fs() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+        let args = [dir_str, "files-rename", "-D", "-n", "Trip: Paris"];
+
+        let (std, _) = run_cli(&[&args[..], &["--target-fs", "ext4"]].concat())?;
+        assert!(std.contains("Trip: Paris 1.jpg\"\n"));
+
+        assert!(run_cli(&[&args[..], &["--target-fs", "fat32"]].concat()).is_err());
+
+        let (std, _) = run_cli(&[&args[..], &["--target-fs", "fat32", "--fix-names"]].concat())?;
+        assert!(std.contains("Trip_ Paris 1.jpg\"\n"));
+
+        // Long names truncated to the same name, cutting off the sequence number
+        write_photo(dir.path().join("2.jpg"), "2025:05:02 12:13:14");
+        let long = "a".repeat(300);
+        let args = [dir_str, "files-rename", "-n", &long];
+        let err =
+            run_cli(&[&args[..], &["--target-fs", "ext4", "--fix-names"]].concat()).unwrap_err();
+        assert!(err.to_string().contains("would be renamed to"));
+        assert!(dir.path().join("1.jpg").exists());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_rename_target_fs_non_utf8() -> Result<()> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let dir = tempfile::tempdir()?;
+        let invalid = dir.path().join(OsStr::from_bytes(b"a.j\xFFg"));
+        write_photo(&invalid, "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b.jpg"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (_, err) = run_cli(&[dir_str, "files-rename", "-n", "p", "--target-fs", "ext4"])?;
+        assert!(err.contains("is not valid UTF-8 and cannot be checked"));
+        assert!(err.contains("Renamed 1 file into 1 directory (1 skipped)"));
+        assert!(invalid.exists());
+        assert!(dir.path().join("p 2.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_name_template() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+        let template = "{name}_{index}_{date:%Y%m%d}{ext}";
+
+        run_cli(&[
+            dir_str,
+            "files-rename",
+            "-n",
+            "p",
+            "--name-template",
+            template,
+        ])?;
+        assert!(dir.path().join("p_1_20250501.jpg").exists());
+        assert!(dir.path().join("p_2_20250502").exists());
+
+        let args = [
+            "photo-dater",
+            "files-rename",
+            "--name-template",
+            "{name} {i}",
+        ];
+        let err = Cli::try_parse_from(args).unwrap_err().to_string();
+        assert!(err.contains("Unknown placeholder {i}"));
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn files_rename_non_utf8() -> Result<()> {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(
+            dir.path().join(OsStr::from_bytes(b"b\xff.J\xffG")),
+            "2025:05:02 12:13:14",
+        );
+        let dir_str = dir.path().to_str().unwrap();
+
+        run_cli(&[dir_str, "files-rename", "-n", "p", "--lowercase-ext"])?;
+        assert!(dir.path().join("p 1.jpg").exists());
+        assert!(dir.path().join(OsStr::from_bytes(b"p 2.j\xffg")).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_by_file_name() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        write_photo(path("a/2.jpg"), "2025:05:01 12:13:14");
+        write_photo(path("b/1.jpg"), "2025:05:02 12:13:14");
+        write_photo(path("b/3.jpg"), "2025:05:03 12:13:14");
+
+        run_cli(&[
+            dir.path().to_str().unwrap(),
+            "files-rename",
+            "-n",
+            "p",
+            "--sort-by",
+            "by-file-name",
+        ])?;
+        assert!(path("b/p 1.jpg").is_file());
+        assert!(path("a/p 2.jpg").is_file());
+        assert!(path("b/p 3.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_slug() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.JPG"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b.jpg"), "2025:05:02 12:13:14");
+
+        run_cli(&[
+            dir.path().to_str().unwrap(),
+            "files-rename",
+            "--name",
+            "Trip to Paris, Day 1!",
+            "--slug",
+        ])?;
+        assert!(dir.path().join("trip-to-paris-day-1-1.JPG").is_file());
+        assert!(dir.path().join("trip-to-paris-day-1-2.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_start() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b.jpg"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join("c.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "files-rename", "-n", "p", "--start", "99", "-D"])?;
+        assert!(std.contains("p 099.jpg"));
+        assert!(std.contains("p 100.jpg"));
+        assert!(std.contains("p 101.jpg"));
+
+        run_cli(&[dir_str, "files-rename", "-n", "p", "--start", "0"])?;
+        assert!(dir.path().join("p 0.jpg").exists());
+        assert!(dir.path().join("p 2.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_scoped() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("a"))?;
+        fs::create_dir(dir.path().join("b"))?;
+        write_photo(dir.path().join("a/x.jpg"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join("a/y.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b/z.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        run_cli(&[
+            dir_str,
+            "files-rename",
+            "-n",
+            "p",
+            "-s",
+            "created",
+            "--scoped",
+        ])?;
+        assert!(dir.path().join("a/p 1.jpg").exists());
+        assert!(dir.path().join("a/p 2.jpg").exists());
+        // Numbering of the second folder does not continue after the first one
+        assert!(dir.path().join("b/p 1.jpg").exists());
+        assert!(!dir.path().join("b/p 3.jpg").exists());
+
+        let args = ["files-rename", "--scoped", "--stable-index", "map"];
+        assert!(Cli::try_parse_from([&["photo-dater"][..], &args].concat()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn files_rename_stable_index() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("photos");
+        write_photo(dir.join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.join("c.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.to_str().unwrap();
+        let map = root.path().join("map.txt");
+        let args = [dir_str, "files-rename", "-n", "p", "-s", "created"];
+        let args = [&args[..], &["--stable-index", map.to_str().unwrap()]].concat();
+
+        run_cli(&args)?;
+        assert!(dir.join("p 1.jpg").is_file());
+        assert!(dir.join("p 2.jpg").is_file());
+        assert_eq!(fs::read_to_string(&map)?, "1\tp 1.jpg\n2\tp 2.jpg\n");
+
+        // A file added in the middle gets a new number instead of shifting the others
+        write_photo(dir.join("b.jpg"), "2025:05:02 12:13:14");
+        run_cli(&args)?;
+        assert!(dir.join("p 1.jpg").is_file());
+        assert!(dir.join("p 2.jpg").is_file());
+        assert!(dir.join("p 3.jpg").is_file());
+        assert!(!dir.join("b.jpg").exists());
+        assert_eq!(
+            fs::read_to_string(&map)?,
+            "1\tp 1.jpg\n2\tp 2.jpg\n3\tp 3.jpg\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn undo_files_rename() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.jpg"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join("b.jpg"), "2025:05:01 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+        let log = dir.path().join("undo.jsonl");
+        let log_str = log.to_str().unwrap();
+        let names = || -> Result<Vec<String>> {
+            let mut names = fs::read_dir(dir.path())?
+                .map(|e| Ok(e?.file_name().to_string_lossy().to_string()))
+                .collect::<Result<Vec<_>>>()?;
+            names.sort();
+            Ok(names)
+        };
+
+        run_cli(&[
+            dir_str,
+            "--undo-log",
+            log_str,
+            "files-rename",
+            "-D",
+            "-n",
+            "p",
+        ])?;
+        assert!(!log.exists());
+
+        run_cli(&[dir_str, "--undo-log", log_str, "files-rename", "-n", "p"])?;
+        assert_eq!(names()?, ["p 1.jpg", "p 2.jpg", "undo.jsonl"]);
+
+        let (std, _) = run_cli(&[dir_str, "undo", log_str])?;
+        assert_eq!(std.lines().count(), 4);
+        assert_eq!(names()?, ["a.jpg", "b.jpg"]);
+        Ok(())
+    }
+
+    #[test]
+    fn stats() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dir_str = dir.path().to_str().unwrap();
+        let (std, _) = run_cli(&[dir_str, "stats"])?;
+        assert_eq!(
+            std,
+            "Files: 0\nWith date: 0\nSpan: 0 days\nDistinct days: 0\n"
+        );
+
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:01 14:15:16");
+        write_photo(dir.path().join("3.jpg"), "2025:06:03 12:13:14");
+        fs::write(dir.path().join("notes.txt"), "no exif here")?;
+        let (std, _) = run_cli(&[dir_str, "stats"])?;
+        assert_eq!(
+            std,
+            "Files: 4\nWith date: 3\nSpan: 33 days\nDistinct days: 2\n\
+             2025-05      2 ##\n\
+             2025-06      1 #\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn count() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let dir_str = dir.path().to_str().unwrap();
+        let (std, _) = run_cli(&[dir_str, "count"])?;
+        assert_eq!(std, "Files: 0\n");
+
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.JPG"), "2025:05:01 14:15:16");
+        write_photo(dir.path().join("3.png"), "2025:06:03 12:13:14");
+        write_photo(dir.path().join("4"), "2025:06:03 12:13:14");
+        fs::write(dir.path().join("notes.txt"), "no exif here")?;
+        let (std, _) = run_cli(&[dir_str, "count"])?;
+        assert_eq!(std, "Files: 4\n(none): 1, jpg: 2, png: 1\n");
+        Ok(())
+    }
+
+    #[test]
+    fn skip_errors() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "yesterday");
+        write_photo(dir.path().join("3.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        assert!(run_cli(&[dir_str, "list"]).is_err());
+
+        let (std, err) = run_cli(&[dir_str, "--skip-errors", "list"])?;
+        assert_eq!(listed_names(&std), ["1.jpg", "3.jpg"]);
+        assert!(err.starts_with("Skipped 1 files that could not be read:\n"));
+        assert!(err.contains("2.jpg"));
+        assert!(err.contains("Failed to parse date"));
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_no_recursive() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2025-05-03/2.jpg"), "2025:05:03 12:13:14");
+
+        let (std, err) = run_cli(&[
+            dir.path().to_str().unwrap(),
+            "--no-recursive",
+            "move-by-days",
+            "--dry-run",
+        ])?;
+        let lines = std.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("1.jpg"));
+        assert!(err.starts_with("Summary: 1 to rename, 0 unchanged, 0 in conflict\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_conflict() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        // Not moved itself, as it has no date
+        fs::create_dir(dir.path().join("2025-05-01"))?;
+        fs::write(dir.path().join("2025-05-01/1.jpg"), "")?;
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (_, err) = run_cli(&[dir_str, "move-by-days", "--dry-run"])?;
+        assert!(err.starts_with("Summary: 0 to rename, 0 unchanged, 1 in conflict\n"));
+
+        let err = run_cli(&[dir_str, "move-by-days"]).unwrap_err();
+        assert!(err.to_string().contains("would overwrite existing file"));
+        assert!(dir.path().join("1.jpg").exists());
+        assert_eq!(fs::read(dir.path().join("2025-05-01/1.jpg"))?, b"");
+        Ok(())
+    }
+
+    #[test]
+    fn config_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b.png"), "2025:05:02 12:13:14");
+        write_photo(dir.path().join("sub/c.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        // Without a config file, only the command line applies
+        let (std, _) = run_cli(&[dir_str, "--ext", "png", "list"])?;
+        assert_eq!(listed_names(&std), ["b.png"]);
+
+        fs::write(
+            dir.path().join(config::CONFIG_NAME),
+            "extensions = [\"jpg\"]\nname-template = \"{name}_{index}{ext}\"\n",
+        )?;
+        let (std, err) = run_cli(&[dir_str, "--verbose", "list"])?;
+        assert_eq!(listed_names(&std), ["a.jpg", "c.jpg"]);
+        assert!(err.contains("Using config file"));
+
+        // Options given on the command line win
+        let (std, _) = run_cli(&[dir_str, "--ext", "png", "list"])?;
+        assert_eq!(listed_names(&std), ["b.png"]);
+        let (std, _) = run_cli(&[dir_str, "--no-config", "list"])?;
+        assert_eq!(listed_names(&std), ["a.jpg", "b.png", "c.jpg"]);
+
+        // The config file is merged with the command line
+        run_cli(&[dir_str, "--no-recursive", "files-rename", "-n", "p"])?;
+        assert!(dir.path().join("p_1.jpg").exists());
+        assert!(dir.path().join("b.png").exists());
+        assert!(dir.path().join("sub/c.jpg").exists());
+
+        run_cli(&[
+            dir_str,
+            "--no-recursive",
+            "files-rename",
+            "-n",
+            "p",
+            "--name-template",
+            "{name} {index}{ext}",
+        ])?;
+        assert!(dir.path().join("p 1.jpg").exists());
+
+        // Flags of the config file can be turned off on the command line
+        fs::write(
+            dir.path().join(config::CONFIG_NAME),
+            "no-recursive = true\n",
+        )?;
+        let (std, _) = run_cli(&[dir_str, "list"])?;
+        assert_eq!(listed_names(&std), ["p 1.jpg", "b.png"]);
+        let (std, _) = run_cli(&[dir_str, "--recursive", "list"])?;
+        assert_eq!(listed_names(&std), ["p 1.jpg", "b.png", "c.jpg"]);
+        assert!(
+            Cli::try_parse_from(["photo-dater", "--recursive", "--no-recursive", "list"]).is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn day_spans() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 10:00:00");
+        write_photo(dir.path().join("2.jpg"), "2025:05:01 14:30:20");
+        write_photo(dir.path().join("3.jpg"), "2025:05:02 09:15:00");
+
+        let (std, _) = run_cli(&[dir.path().to_str().unwrap(), "day-spans"])?;
+        assert_eq!(
+            std,
+            "2025-05-01 10:00:00 - 14:30:20 (4:30:20)\n\
+             2025-05-02 09:15:00 - 09:15:00 (0:00:00)\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_date_tree() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "move-by-date-tree", "--levels", "year/month"])?;
+        assert!(std.starts_with("Move file"));
+        assert!(dir.path().join("2025/05/1.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_verify_after() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 12:13:14");
+
+        let (_, err) = run_cli(&[
+            dir.path().to_str().unwrap(),
+            "move-by-days",
+            "--verify-after",
+        ])?;
+        assert_eq!(
+            err,
+            "Moved 2 files into 2 directories (0 skipped)\nVerified 2 moved files\n"
+        );
+        assert!(dir.path().join("2025-05-01/1.jpg").is_file());
+        assert!(dir.path().join("2025-05-03/2.jpg").is_file());
+
+        // Moved files are read where they were moved, whatever was read before
+        let verified = "Verified 1 moved files\n";
+        let path = |name: &str| dir.path().join(name);
+        write_photo(path("3.jpg"), "2025:05:04 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+        let args = [dir_str, "--no-recursive", "move-by-days", "--verify-after"];
+        assert!(run_cli(&args)?.1.ends_with(verified));
+        assert!(path("2025-05-04/3.jpg").is_file());
+
+        let out = tempfile::tempdir()?;
+        write_photo(path("4.jpg"), "2025:05:05 12:13:14");
+        let args = ["--no-recursive", "move-by-days", "--verify-after", "--out"];
+        let (_, err) = run_cli(&[&[dir_str], &args[..], &[out.path().to_str().unwrap()]].concat())?;
+        assert!(err.ends_with(verified));
+        assert!(out.path().join("2025-05-05/4.jpg").is_file());
+
+        write_photo(path("5.jpg"), "2025:05:06 12:13:14");
+        let (mut std, mut err) = (Vec::new(), Vec::new());
+        let cli = Cli::parse_from([
+            "photo-dater",
+            "--files-from",
+            "-",
+            "move-by-days",
+            "--verify-after",
+        ]);
+        let list = format!("{}\n", path("5.jpg").display());
+        execute(cli, list.as_bytes(), &mut std, &mut err)?;
+        assert!(String::from_utf8(err)?.ends_with(verified));
+        assert!(path("2025-05-06/5.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn tree() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:01 14:15:16");
+        write_photo(dir.path().join("3.jpg"), "2025:05:03 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "tree"])?;
+        assert_eq!(std, "2025-05-01/ (2 files)\n2025-05-03/ (1 file)\n");
+        let (std, _) = run_cli(&[dir_str, "tree", "--names"])?;
+        assert_eq!(
+            std,
+            "2025-05-01/ (2 files)\n  1.jpg\n  2.jpg\n2025-05-03/ (1 file)\n  3.jpg\n"
+        );
+        assert!(!dir.path().join("2025-05-01").exists());
+
+        // Nested files are moved next to them, unless flattened
+        write_photo(dir.path().join("a/1.jpg"), "2025:05:01 18:00:00");
+        let (std, _) = run_cli(&[dir_str, "tree"])?;
+        assert_eq!(
+            std,
+            "2025-05-01/ (2 files)\n2025-05-03/ (1 file)\na/2025-05-01/ (1 file)\n"
+        );
+        let (std, _) = run_cli(&[dir_str, "tree", "--flatten", "--names"])?;
+        assert_eq!(
+            std,
+            "2025-05-01/ (3 files)\n  1.jpg\n  2.jpg\n  1_1.jpg\n2025-05-03/ (1 file)\n  3.jpg\n"
+        );
+        let out = tempfile::tempdir()?;
+        let (std, _) = run_cli(&[dir_str, "tree", "--out", out.path().to_str().unwrap()])?;
+        assert_eq!(std, "2025-05-01/ (3 files)\n2025-05-03/ (1 file)\n");
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_flatten() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("a/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b/c/1.jpg"), "2025:05:01 14:15:16");
+        write_photo(dir.path().join("b/2.jpg"), "2025:05:03 12:13:14");
+
+        run_cli(&[dir.path().to_str().unwrap(), "move-by-days", "--flatten"])?;
+        assert!(dir.path().join("2025-05-01/1.jpg").is_file());
+        assert!(dir.path().join("2025-05-01/1_1.jpg").is_file());
+        assert!(dir.path().join("2025-05-03/2.jpg").is_file());
+        assert!(!dir.path().join("a/2025-05-01").exists());
+        assert!(!dir.path().join("b/2025-05-03").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_out() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let out = tempfile::tempdir()?;
+        write_photo(dir.path().join("a/1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("b/1.jpg"), "2025:05:01 14:15:16");
+        write_photo(dir.path().join("2.jpg"), "2025:05:03 12:13:14");
+        write_photo(out.path().join("2025-05-03/2.jpg"), "2025:05:03 10:00:00");
+
+        run_cli(&[
+            dir.path().to_str().unwrap(),
+            "move-by-days",
+            "--out",
+            out.path().to_str().unwrap(),
+        ])?;
+        assert!(out.path().join("2025-05-01/1.jpg").is_file());
+        assert!(out.path().join("2025-05-01/1_1.jpg").is_file());
+        assert!(out.path().join("2025-05-03/2.jpg").is_file());
+        assert!(out.path().join("2025-05-03/2_1.jpg").is_file());
+        assert!(!dir.path().join("2.jpg").exists());
+        assert!(!dir.path().join("2025-05-01").exists());
+
+        let args = ["photo-dater", "move-by-days", "--flatten", "--out", "x"];
+        assert!(Cli::try_parse_from(args).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_location() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        test_utils::write_photo_at(dir.path().join("1.jpg"), "2025:05:01 10:00:00", 48.85, 2.35);
+        test_utils::write_photo_at(dir.path().join("2.jpg"), "2025:05:01 18:00:00", 45.76, 4.84);
+        write_photo(dir.path().join("3.jpg"), "2025:05:02 12:13:14");
+
+        run_cli(&[dir.path().to_str().unwrap(), "move-by-location"])?;
+        assert!(dir.path().join("2025-05-01 48.85,2.35/1.jpg").is_file());
+        assert!(dir.path().join("2025-05-01 45.76,4.84/2.jpg").is_file());
+        assert!(dir.path().join("2025-05-02/3.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn move_by_days_dedupe_content() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        fs::create_dir(dir.path().join("copy"))?;
+        fs::copy(dir.path().join("1.jpg"), dir.path().join("copy/1.jpg"))?;
+        let args = [
+            dir.path().to_str().unwrap(),
+            "move-by-days",
+            "--dedupe-content",
+        ];
+
+        // The skipped duplicates do not break the JSON plan
+        let (std, err) = run_cli(&[&args[..], &["--dry-run-json"]].concat())?;
+        let plan = serde_json::from_str::<serde_json::Value>(&std)?;
+        assert_eq!(plan.as_array().map(Vec::len), Some(1));
+        assert!(err.starts_with("Skip duplicate"));
+
+        let (std, err) = run_cli(&args)?;
+        assert!(std.starts_with("Move file"));
+        assert!(err.starts_with("Skip duplicate"));
+        assert!(err.ends_with("Moved 1 file into 1 directory (1 skipped)\n"));
+        // Either of the copies is moved, the other one stays in place
+        let moved = ["2025-05-01/1.jpg", "copy/2025-05-01/1.jpg"].map(|p| dir.path().join(p));
+        let kept = ["1.jpg", "copy/1.jpg"].map(|p| dir.path().join(p));
+        assert_eq!(moved.iter().filter(|p| p.is_file()).count(), 1);
+        assert_eq!(kept.iter().filter(|p| p.is_file()).count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn plan_count_summary() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("1.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("2.jpg"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, err) = run_cli(&[dir_str, "files-rename", "-D", "-n", "p"])?;
+        assert_eq!(
+            err,
+            "Summary: 2 to rename, 0 unchanged, 0 in conflict\nWould rename 2 files into 1 directory (0 skipped)\n"
+        );
+        assert!(!std.contains("Would"));
+
+        let (_, err) = run_cli(&[dir_str, "rename", "5", "-D"])?;
+        assert_eq!(err, "Would rename 1 directory (0 skipped)\n");
+        Ok(())
+    }
+
+    #[test]
+    fn global_dry_run() -> Result<()> {
+        let root = tempfile::tempdir()?;
+        let dir = root.path().join("Trip");
+        write_photo(dir.join("a.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.join("b.jpg"), "2025:05:01 14:15:16");
+        fs::copy(dir.join("a.jpg"), dir.join("c.jpg"))?;
+        fs::create_dir(dir.join("2025-04-01"))?;
+        let dir_str = dir.to_str().unwrap();
+        let (plan, log) = (
+            root.path().join("plan.json"),
+            root.path().join("undo.jsonl"),
+        );
+        let (plan_str, log_str) = (plan.to_str().unwrap(), log.to_str().unwrap());
+        let (std, _) = run_cli(&[dir_str, "--dry-run-json", "files-rename", "-n", "p"])?;
+        fs::write(&plan, std)?;
+        // Record moves of another directory to undo
+        let other = root.path().join("Other");
+        write_photo(other.join("a.jpg"), "2025:05:01 12:13:14");
+        let other_str = other.to_str().unwrap();
+        run_cli(&[other_str, "--undo-log", log_str, "files-rename", "-n", "p"])?;
+        let snapshot = || -> Result<Vec<PathBuf>> {
+            let mut paths = fs::read_dir(&dir)?
+                .map(|entry| Ok(entry?.path()))
+                .collect::<io::Result<Vec<_>>>()?;
+            paths.sort();
+            Ok(paths)
+        };
+        let before = snapshot()?;
+
+        for args in [
+            &["--dry-run", "rename"][..],
+            &["-D", "files-rename", "-n", "p"],
+            &["--no-act", "move-by-days"],
+            &["move-by-month", "--no-act"],
+            &["--dry-run", "prune-empty"],
+            &["--dry-run", "dedupe", "--delete"],
+            &["--dry-run", "apply", "--plan", plan_str],
+        ] {
+            let (std, _) = run_cli(&[&[dir_str], args].concat())?;
+            assert!(!std.is_empty(), "{args:?} printed no plan");
+            assert_eq!(snapshot()?, before, "{args:?} changed files");
+        }
+        assert!(dir.exists());
+
+        let (std, _) = run_cli(&[other_str, "--dry-run", "undo", log_str])?;
+        assert!(std.starts_with("Move back"));
+        assert!(other.join("p 1.jpg").exists());
+        assert!(log.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn dry_run_json() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("b.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("a.jpg"), "2025:05:02 12:13:14");
+        let path = |name: &str| dir.path().join(name).to_str().unwrap().to_string();
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "--dry-run-json", "files-rename", "-n", "p"])?;
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&std)?,
+            serde_json::json!([
+                {"from": path("a.jpg"), "to": path("p 1.jpg")},
+                {"from": path("b.jpg"), "to": path("p 2.jpg")},
+            ])
+        );
+        assert!(dir.path().join("a.jpg").exists());
+
+        let (std, _) = run_cli(&[dir_str, "move-by-days", "--dry-run-json"])?;
+        let plan = serde_json::from_str::<Vec<undo_log::Entry>>(&std)?;
+        assert_eq!(plan.len(), 2);
+        assert!(!dir.path().join("2025-05-01").exists());
+
+        let (std, _) = run_cli(&[dir_str, "rename", "5", "--dry-run-json"])?;
+        let plan = serde_json::from_str::<Vec<undo_log::Entry>>(&std)?;
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].from, dir.path());
+        assert!(dir.path().exists());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_plan() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("b.jpg"), "2025:05:01 12:13:14");
+        write_photo(dir.path().join("a.jpg"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+        let plan = dir.path().join("plan.json");
+
+        let (std, _) = run_cli(&[
+            dir_str,
+            "--format",
+            "json",
+            "files-rename",
+            "-n",
+            "p",
+            "--dry-run",
+        ])?;
+        fs::write(&plan, &std)?;
+        assert!(dir.path().join("a.jpg").exists());
+
+        run_cli(&[dir_str, "apply", "--plan", plan.to_str().unwrap()])?;
+        assert!(dir.path().join("p 1.jpg").is_file());
+        assert!(dir.path().join("p 2.jpg").is_file());
+        assert!(!dir.path().join("a.jpg").exists());
+
+        // Files are no longer where the plan expects them
+        assert!(run_cli(&[dir_str, "apply", "--plan", plan.to_str().unwrap()]).is_err());
+        assert!(dir.path().join("p 1.jpg").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn prune_empty() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("2025-05-01/1.jpg"), "2025:05:01 12:13:14");
+        fs::create_dir(dir.path().join("2025-05-02"))?;
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "prune-empty", "--dry-run"])?;
+        assert!(std.ends_with("2025-05-02\"\n"));
+        assert!(dir.path().join("2025-05-02").exists());
+
+        run_cli(&[dir_str, "prune-empty"])?;
+        assert!(!dir.path().join("2025-05-02").exists());
+        assert!(dir.path().join("2025-05-01/1.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn dedupe() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        write_photo(dir.path().join("b/1.jpg"), "2025:05:01 12:13:14");
+        fs::copy(dir.path().join("b/1.jpg"), dir.path().join("a.jpg"))?;
+        write_photo(dir.path().join("c.jpg"), "2025:05:02 12:13:14");
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, err) = run_cli(&[dir_str, "dedupe"])?;
+        assert_eq!(
+            std,
+            format!(
+                "Duplicates [{:?}, {:?}]\n",
+                dir.path().join("a.jpg"),
+                dir.path().join("b/1.jpg")
+            )
+        );
+        assert_eq!(err, "");
+        assert!(dir.path().join("b/1.jpg").exists());
+
+        let (_, err) = run_cli(&[dir_str, "dedupe", "--delete"])?;
+        assert_eq!(err, "Removed 1 duplicate files\n");
+        assert!(dir.path().join("a.jpg").exists());
+        assert!(!dir.path().join("b/1.jpg").exists());
+        assert!(dir.path().join("c.jpg").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn suggest_merge() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        [
+            "2025-05-01",
+            "2025-05-02",
+            "2025-05-03",
+            "2025-05-10",
+            "Other",
+        ]
+        .iter()
+        .try_for_each(|name| fs::create_dir(dir.path().join(name)))?;
+        let dir_str = dir.path().to_str().unwrap();
+
+        let (std, _) = run_cli(&[dir_str, "suggest-merge"])?;
+        assert_eq!(
+            std,
+            "Merge [\"2025-05-01\", \"2025-05-02\", \"2025-05-03\"] => \"2025-05-01 - 03\"\n"
+        );
+        assert!(dir.path().join("2025-05-01").exists());
+
+        let (std, _) = run_cli(&[dir_str, "suggest-merge", "7"])?;
+        assert!(std.ends_with("=> \"2025-05-01 - 10\"\n"));
+        Ok(())
+    }
 }
@@ -1,11 +1,36 @@
-use anyhow::Result;
+use photo_dater::PhotoDaterError;
 use std::env;
 use std::io;
+use std::process::ExitCode;
+
+/// Exit code for an interval of files larger or smaller than allowed, e.g. by `check`.
+const EXIT_INTERVAL_OUT_OF_RANGE: u8 = 3;
+
+/// Exit code for a directory without any files with dates.
+const EXIT_NO_FILES_WITH_DATES: u8 = 4;
 
 /// Main entry point that runs the photo_dater application.
 ///
 /// Passes command line arguments and standard I/O streams to the library's
-/// run function for processing.
-fn main() -> Result<()> {
-    photo_dater::run(env::args(), io::stdout(), io::stderr())
+/// run function for processing. Failures are reported on standard error and
+/// mapped to exit codes, so scripts can tell them apart.
+fn main() -> ExitCode {
+    match photo_dater::run(env::args(), io::stdout(), io::stderr()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            match err.downcast_ref() {
+                Some(
+                    PhotoDaterError::IntervalTooLarge { .. }
+                    | PhotoDaterError::IntervalTooLargeHours { .. }
+                    | PhotoDaterError::IntervalTooSmall { .. }
+                    | PhotoDaterError::IntervalTooSmallHours { .. },
+                ) => EXIT_INTERVAL_OUT_OF_RANGE.into(),
+                Some(PhotoDaterError::NoFilesWithDates | PhotoDaterError::NoFilesInRange) => {
+                    EXIT_NO_FILES_WITH_DATES.into()
+                }
+                _ => ExitCode::FAILURE,
+            }
+        }
+    }
 }
@@ -0,0 +1,511 @@
+use crate::files_interval::{FilesInterval, NameFormat};
+use anyhow::{anyhow, Result};
+use chrono::NaiveDateTime;
+use std::{
+    ffi::{OsStr, OsString},
+    fmt::Write,
+    str::FromStr,
+};
+
+/// Layout of file names used when no template is given, e.g. `Trip 0001.jpg`.
+const DEFAULT_TEMPLATE: &str = "{name} {index}{ext}";
+
+/// Format of `{date}` placeholders without an explicit format.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// A single piece of a [`NameTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    /// Text copied to the name as is
+    Literal(String),
+    /// `{name}`, the base name of renamed files
+    Name,
+    /// `{index}`, the sequence number padded to the number of digits
+    Index,
+    /// `{date:FORMAT}`, the creation date in the given `strftime` format
+    Date(String),
+    /// `{ext}`, the original extension including the dot, or nothing
+    Ext,
+}
+
+impl Part {
+    /// Parses the content of a placeholder between braces.
+    fn parse(placeholder: &str) -> Result<Self> {
+        match placeholder.split_once(':') {
+            None if placeholder == "name" => Ok(Part::Name),
+            None if placeholder == "index" => Ok(Part::Index),
+            None if placeholder == "date" => Ok(Part::Date(DEFAULT_DATE_FORMAT.to_string())),
+            None if placeholder == "ext" => Ok(Part::Ext),
+            Some(("date", format)) => {
+                // Fails on unknown specifiers and on time zone ones, creation dates have no zone
+                let mut sample = String::new();
+                if write!(sample, "{}", NaiveDateTime::default().format(format)).is_err() {
+                    return Err(anyhow!("Invalid date format {format:?} in name template"));
+                }
+                Ok(Part::Date(format.to_string()))
+            }
+            _ => Err(anyhow!(
+                "Unknown placeholder {{{placeholder}}} in name template, \
+                 expected {{name}}, {{index}}, {{date}}, {{date:FORMAT}} or {{ext}}"
+            )),
+        }
+    }
+}
+
+/// Layout of file names generated by `files-rename`, such as
+/// `{name}_{index}_{date:%Y%m%d}{ext}`.
+///
+/// Literal braces are written doubled, `{{` and `}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameTemplate {
+    parts: Vec<Part>,
+    /// Turn the rendered name into a slug, see [`slugify`]
+    slug: bool,
+}
+
+impl Default for NameTemplate {
+    fn default() -> Self {
+        DEFAULT_TEMPLATE.parse().unwrap()
+    }
+}
+
+/// Turns `text` into a lowercase slug for web galleries, e.g. `Trip to Paris!` into
+/// `trip-to-paris`. Whitespace becomes `-`, characters other than alphanumerics, `-`
+/// and `_` are removed, `-` next to another `-` or `_` is dropped and `-` at the edges
+/// are trimmed.
+fn slugify(text: &str) -> String {
+    let slug = text
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| {
+            if c.is_whitespace() {
+                Some('-')
+            } else {
+                (c.is_alphanumeric() || c == '-' || c == '_').then_some(c)
+            }
+        })
+        .fold(String::new(), |mut slug, c| {
+            match c {
+                '-' if slug.ends_with(['-', '_']) => {}
+                '_' if slug.ends_with('-') => {
+                    slug.pop();
+                    slug.push(c);
+                }
+                c => slug.push(c),
+            }
+            slug
+        });
+    slug.trim_matches('-').to_string()
+}
+
+impl FromStr for NameTemplate {
+    type Err = anyhow::Error;
+
+    /// Parses the template, failing on unknown placeholders and unmatched braces.
+    fn from_str(template: &str) -> Result<Self> {
+        Ok(Self {
+            parts: parse_parts(template, "name", Part::Literal, Part::parse)?,
+            slug: false,
+        })
+    }
+}
+
+/// Splits `template` into literal text and placeholders parsed by `placeholder`.
+/// Literal braces are written doubled, `{{` and `}}`. The `kind` of the template is
+/// used in error messages.
+fn parse_parts<P>(
+    template: &str,
+    kind: &str,
+    literal_part: fn(String) -> P,
+    placeholder_part: fn(&str) -> Result<P>,
+) -> Result<Vec<P>> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.as_str().starts_with(c) => {
+                chars.next();
+                literal.push(c);
+            }
+            '{' => {
+                let (placeholder, rest) = chars.as_str().split_once('}').ok_or(anyhow!(
+                    "Unclosed placeholder in {kind} template {template:?}"
+                ))?;
+                chars = rest.chars();
+                if !literal.is_empty() {
+                    parts.push(literal_part(std::mem::take(&mut literal)));
+                }
+                parts.push(placeholder_part(placeholder)?);
+            }
+            '}' => {
+                return Err(anyhow!(
+                    "Unmatched '}}' in {kind} template {template:?}, use '}}}}' for a literal brace"
+                ))
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        parts.push(literal_part(literal));
+    }
+    Ok(parts)
+}
+
+impl NameTemplate {
+    /// Turns the rendered names into slugs, e.g. `Trip Paris 01.JPG` into `trip-paris-01.JPG`.
+    /// The extension substituted for `{ext}` is kept as is.
+    pub fn slug(self) -> Self {
+        Self { slug: true, ..self }
+    }
+
+    /// Generates the file name of a renamed file.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Base name substituted for `{name}`
+    /// * `index` - Sequence number substituted for `{index}`, zero-padded to `digits`
+    /// * `created` - Creation date substituted for `{date}`
+    /// * `extension` - Original extension without the dot, if the file has one. It is kept
+    ///   as is, even if it is not valid UTF-8.
+    pub fn render(
+        &self,
+        name: &str,
+        index: usize,
+        digits: usize,
+        created: &NaiveDateTime,
+        extension: Option<&OsStr>,
+    ) -> OsString {
+        let mut rendered = OsString::new();
+        // Text since the last `{ext}`, so slugs are made of whole runs of text
+        let mut text = String::new();
+        let flush = |text: &mut String, rendered: &mut OsString| {
+            let text = std::mem::take(text);
+            rendered.push(if self.slug { slugify(&text) } else { text });
+        };
+        for part in &self.parts {
+            match part {
+                Part::Literal(literal) => text.push_str(literal),
+                Part::Name => text.push_str(name),
+                Part::Index => text.push_str(&format!("{index:0digits$}")),
+                Part::Date(format) => text.push_str(&created.format(format).to_string()),
+                Part::Ext => {
+                    flush(&mut text, &mut rendered);
+                    if let Some(extension) = extension {
+                        rendered.push(".");
+                        rendered.push(extension);
+                    }
+                }
+            }
+        }
+        flush(&mut text, &mut rendered);
+        rendered
+    }
+}
+
+/// A single piece of a [`DirectoryTemplate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DirectoryPart {
+    /// Text copied to the name as is
+    Literal(String),
+    /// `{name}`, the descriptive part of the current name without its dates
+    Name,
+    /// `{interval}`, the date range of the files
+    Interval,
+}
+
+impl DirectoryPart {
+    /// Parses the content of a placeholder between braces.
+    fn parse(placeholder: &str) -> Result<Self> {
+        match placeholder {
+            "name" => Ok(DirectoryPart::Name),
+            "interval" => Ok(DirectoryPart::Interval),
+            _ => Err(anyhow!(
+                "Unknown placeholder {{{placeholder}}} in directory template, \
+                 expected {{name}} or {{interval}}"
+            )),
+        }
+    }
+}
+
+/// Layout of directory names generated by `rename`, such as `{name} [{interval}]`.
+///
+/// The template has to contain `{interval}` exactly once and `{name}` at most once.
+/// Literal braces are written doubled, `{{` and `}}`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryTemplate(Vec<DirectoryPart>);
+
+impl FromStr for DirectoryTemplate {
+    type Err = anyhow::Error;
+
+    /// Parses the template, failing on unknown or repeated placeholders and unmatched braces.
+    fn from_str(template: &str) -> Result<Self> {
+        let parts = parse_parts(
+            template,
+            "directory",
+            DirectoryPart::Literal,
+            DirectoryPart::parse,
+        )?;
+        let count = |part| parts.iter().filter(|p| **p == part).count();
+        if count(DirectoryPart::Interval) != 1 {
+            return Err(anyhow!(
+                "Directory template {template:?} has to contain {{interval}} exactly once"
+            ));
+        }
+        if count(DirectoryPart::Name) > 1 {
+            return Err(anyhow!(
+                "Directory template {template:?} can contain {{name}} only once"
+            ));
+        }
+        Ok(Self(parts))
+    }
+}
+
+impl DirectoryTemplate {
+    /// Layout used for an empty `{name}`, without the placeholder and without
+    /// whitespace at the start and the end, e.g. `[{interval}]` for `{name} [{interval}]`.
+    fn without_name(&self) -> Vec<DirectoryPart> {
+        let mut parts: Vec<_> = self
+            .0
+            .iter()
+            .filter(|part| **part != DirectoryPart::Name)
+            .cloned()
+            .collect();
+        if let Some(DirectoryPart::Literal(literal)) = parts.first_mut() {
+            *literal = literal.trim_start().to_string();
+        }
+        if let Some(DirectoryPart::Literal(literal)) = parts.last_mut() {
+            *literal = literal.trim_end().to_string();
+        }
+        parts
+    }
+
+    /// Generates the directory name from the descriptive `name` and the formatted
+    /// `interval`. An empty name leaves out the whitespace around it at the edges,
+    /// e.g. `{name} [{interval}]` gives `[2025-05-01]`.
+    pub fn render(&self, name: &str, interval: &str) -> String {
+        let parts = if name.is_empty() {
+            self.without_name()
+        } else {
+            self.0.clone()
+        };
+        parts
+            .iter()
+            .map(|part| match part {
+                DirectoryPart::Literal(literal) => literal,
+                DirectoryPart::Name => name,
+                DirectoryPart::Interval => interval,
+            })
+            .collect()
+    }
+
+    /// Recovers the interval and the descriptive name from a directory name laid out
+    /// by the template, the inverse of [`DirectoryTemplate::render`]. Returns `None`
+    /// if the name does not follow the template.
+    pub fn split<'a>(
+        &self,
+        name: &'a str,
+        format: &NameFormat,
+    ) -> Option<(FilesInterval, &'a str)> {
+        match_parts(&self.without_name(), name, format)
+            .map(|(interval, _)| (interval, ""))
+            .or_else(|| match_parts(&self.0, name, format))
+            .and_then(|(interval, name)| interval.map(|interval| (interval, name)))
+    }
+}
+
+/// Matches `name` against `parts`, preferring the longest interval and name when
+/// more splits are possible. Returns the matched interval and name, if the parts
+/// contain them.
+fn match_parts<'a>(
+    parts: &[DirectoryPart],
+    name: &'a str,
+    format: &NameFormat,
+) -> Option<(Option<FilesInterval>, &'a str)> {
+    let Some((part, rest)) = parts.split_first() else {
+        return name.is_empty().then_some((None, ""));
+    };
+    if let DirectoryPart::Literal(literal) = part {
+        return match_parts(rest, name.strip_prefix(literal.as_str())?, format);
+    }
+    (0..=name.len())
+        .rev()
+        .filter(|end| name.is_char_boundary(*end))
+        .find_map(|end| {
+            let (matched, remaining) = name.split_at(end);
+            if *part == DirectoryPart::Interval {
+                let interval = FilesInterval::parse(matched, format).ok()?;
+                let (_, rest_name) = match_parts(rest, remaining, format)?;
+                Some((Some(interval), rest_name))
+            } else {
+                let (interval, _) = match_parts(rest, remaining, format)?;
+                Some((interval, matched.trim()))
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() -> Result<()> {
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14")?;
+        let render = |template: &str, extension: Option<&str>| -> Result<OsString> {
+            Ok(template.parse::<NameTemplate>()?.render(
+                "Trip",
+                7,
+                3,
+                &created,
+                extension.map(OsStr::new),
+            ))
+        };
+
+        assert_eq!(
+            NameTemplate::default().render("Trip", 7, 3, &created, Some(OsStr::new("jpg"))),
+            "Trip 007.jpg"
+        );
+        assert_eq!(
+            NameTemplate::default().render("Trip", 7, 3, &created, None),
+            "Trip 007"
+        );
+        assert_eq!(
+            render("{name}_{index}_{date:%Y%m%d}{ext}", Some("jpg"))?,
+            "Trip_007_20250501.jpg"
+        );
+        assert_eq!(
+            render("{date} {date:%H-%M-%S} {index}{ext}", None)?,
+            "2025-05-01 12-13-14 007"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn render_slug() -> Result<()> {
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14")?;
+        let render = |template: NameTemplate, name: &str| {
+            template
+                .slug()
+                .render(name, 1, 4, &created, Some(OsStr::new("JPG")))
+        };
+
+        assert_eq!(render(NameTemplate::default(), "photo"), "photo-0001.JPG");
+        assert_eq!(
+            render(NameTemplate::default(), "Trip  to Paris"),
+            "trip-to-paris-0001.JPG"
+        );
+        assert_eq!(
+            render(NameTemplate::default(), "Trip - Paris (Day 2)!"),
+            "trip-paris-day-2-0001.JPG"
+        );
+        assert_eq!(
+            render(NameTemplate::default(), "Žluťoučký kůň"),
+            "žluťoučký-kůň-0001.JPG"
+        );
+        assert_eq!(
+            render(
+                "{name}_{index} {date:%d.%m.}{ext}".parse()?,
+                " Trip, Paris "
+            ),
+            "trip-paris_0001-0105.JPG"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn escaped_braces() -> Result<()> {
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14")?;
+        let template = "{{{name}}} {{index}} }}{index}{ext}".parse::<NameTemplate>()?;
+        assert_eq!(
+            template.render("Trip", 1, 2, &created, Some(OsStr::new("jpg"))),
+            "{Trip} {index} }01.jpg"
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn render_non_utf8_extension() -> Result<()> {
+        use std::os::unix::ffi::OsStrExt;
+
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14")?;
+        let extension = OsStr::from_bytes(b"j\xffg");
+        assert_eq!(
+            NameTemplate::default()
+                .render("Trip", 1, 1, &created, Some(extension))
+                .as_bytes(),
+            b"Trip 1.j\xffg"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_templates() {
+        let error = |template: &str| template.parse::<NameTemplate>().unwrap_err().to_string();
+
+        assert!(error("{name} {number}").contains("Unknown placeholder {number}"));
+        assert!(error("{name:upper}").contains("Unknown placeholder {name:upper}"));
+        assert!(error("{name} {index").contains("Unclosed placeholder"));
+        assert!(error("{name} }").contains("Unmatched '}'"));
+        assert!(error("{date:%Q}").contains("Invalid date format"));
+        assert!(error("{date:%z}").contains("Invalid date format"));
+        assert!(error("{date:%:z}").contains("Invalid date format"));
+        assert!(error("{date:%Y%m%d %Z}").contains("Invalid date format"));
+    }
+
+    #[test]
+    fn directory_template() -> Result<()> {
+        let format = NameFormat::default();
+        let interval = || FilesInterval::from_str("2025-05-01 - 03").unwrap();
+        let split = |template: &str, name: &'static str| -> Result<_> {
+            Ok(template.parse::<DirectoryTemplate>()?.split(name, &format))
+        };
+
+        let bracketed = "{name} [{interval}]".parse::<DirectoryTemplate>()?;
+        assert_eq!(
+            bracketed.render("Trip Paris", "2025-05-01 - 03"),
+            "Trip Paris [2025-05-01 - 03]"
+        );
+        assert_eq!(bracketed.render("", "2025-05-01"), "[2025-05-01]");
+        assert_eq!(
+            split("{name} [{interval}]", "Trip Paris [2025-05-01 - 03]")?,
+            Some((interval(), "Trip Paris"))
+        );
+        assert_eq!(
+            split("{name} [{interval}]", "[2025-05-01 - 03]")?,
+            Some((interval(), ""))
+        );
+        assert_eq!(split("{name} [{interval}]", "Trip [Paris]")?, None);
+
+        assert_eq!(
+            split("{name} {interval}", "Trip 2025-05-01 - 03")?,
+            Some((interval(), "Trip"))
+        );
+        assert_eq!(
+            split("{interval} {name}", "2025-05-01 - 03 Trip 2")?,
+            Some((interval(), "Trip 2"))
+        );
+        assert_eq!(
+            split("{interval} {name}", "2025-05-01 - 03")?,
+            Some((interval(), ""))
+        );
+        assert_eq!(split("{interval} {name}", "Trip 2025-05-01")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_directory_templates() {
+        let error = |template: &str| {
+            template
+                .parse::<DirectoryTemplate>()
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert!(error("{name} {date}").contains("Unknown placeholder {date}"));
+        assert!(error("{name}").contains("{interval} exactly once"));
+        assert!(error("{interval} {interval}").contains("{interval} exactly once"));
+        assert!(error("{name} {interval} {name}").contains("{name} only once"));
+        assert!(error("{name} [{interval]").contains("Unclosed placeholder"));
+    }
+}
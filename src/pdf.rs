@@ -0,0 +1,66 @@
+use chrono::NaiveDateTime;
+use lopdf::Document;
+
+/// File extension of documents searched for the creation date.
+pub const EXTENSION: &str = "pdf";
+
+/// Parses a PDF date such as `D:20250501121314+02'00'`.
+///
+/// The `D:` prefix is optional and the time zone suffix following the time is
+/// ignored. Omitted hours, minutes and seconds (e.g. `D:20250501`) are set to zero.
+fn parse_date(date: &str) -> Option<NaiveDateTime> {
+    let date = date.strip_prefix("D:").unwrap_or(date);
+    let digits = date.len() - date.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits < 8 {
+        return None;
+    }
+    let date = format!("{:0<14}", &date[..digits.min(14)]);
+    NaiveDateTime::parse_from_str(&date, "%Y%m%d%H%M%S").ok()
+}
+
+/// Reads the creation date from the document information dictionary of a PDF file.
+///
+/// Returns `None` if the data is not a PDF document or it has no valid `CreationDate`.
+pub fn read(data: &[u8]) -> Option<NaiveDateTime> {
+    let document = Document::load_mem(data).ok()?;
+    let (_, info) = document
+        .dereference(document.trailer.get(b"Info").ok()?)
+        .ok()?;
+    let date = info
+        .as_dict()
+        .ok()?
+        .get(b"CreationDate")
+        .ok()?
+        .as_str()
+        .ok()?;
+    parse_date(std::str::from_utf8(date).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::pdf;
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_date_forms() {
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14").ok();
+        assert_eq!(parse_date("D:20250501121314+02'00'"), created);
+        assert_eq!(parse_date("D:20250501121314Z"), created);
+        assert_eq!(parse_date("20250501121314"), created);
+        assert_eq!(
+            parse_date("D:20250501"),
+            NaiveDateTime::from_str("2025-05-01T00:00:00").ok()
+        );
+        assert_eq!(parse_date("D:2025"), None);
+        assert_eq!(parse_date("May 1, 2025"), None);
+    }
+
+    #[test]
+    fn read_document() {
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14").ok();
+        assert_eq!(read(&pdf(Some("D:20250501121314+02'00'"))), created);
+        assert_eq!(read(&pdf(None)), None);
+        assert_eq!(read(b"not a pdf"), None);
+    }
+}
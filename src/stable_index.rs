@@ -0,0 +1,181 @@
+use anyhow::{anyhow, Context, Result};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Persistent mapping from file names to their sequence numbers.
+///
+/// Used when renaming files so that files seen in a previous run keep their
+/// numbers and only new files get new ones. Entries of renamed files are moved to
+/// their new names, see [`StableIndex::rename`], so the files are recognized by the
+/// names they have on disk. The map is stored as a text file with one
+/// `<index>\t<file name>` entry per line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct StableIndex {
+    numbers: BTreeMap<String, usize>,
+    /// Lowest sequence number assigned to new files
+    start: usize,
+}
+
+impl Default for StableIndex {
+    fn default() -> Self {
+        Self {
+            numbers: BTreeMap::new(),
+            start: 1,
+        }
+    }
+}
+
+impl StableIndex {
+    /// Reads the index map from the given file, returning an empty map if it does not exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or a line is not in the
+    /// `<index>\t<file name>` format.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        fs::read_to_string(path)?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (index, name) = line
+                    .split_once('\t')
+                    .ok_or(anyhow!("Missing tab separator in line {line:?}"))?;
+                let index = index
+                    .parse()
+                    .context(format!("Invalid index in line {line:?}"))?;
+                Ok((name.to_string(), index))
+            })
+            .collect::<Result<_>>()
+            .map(|numbers| Self {
+                numbers,
+                ..Self::default()
+            })
+            .context(format!("Path: {path:?}"))
+    }
+
+    /// Sets the lowest sequence number assigned to new files, 1 by default.
+    pub fn with_start(self, start: usize) -> Self {
+        Self { start, ..self }
+    }
+
+    /// Writes the index map to the given file, ordered by index.
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut entries = self.numbers.iter().collect::<Vec<_>>();
+        entries.sort_by_key(|(_, index)| **index);
+        Ok(fs::write(
+            path,
+            entries
+                .into_iter()
+                .map(|(name, index)| format!("{index}\t{name}\n"))
+                .collect::<String>(),
+        )?)
+    }
+
+    /// Returns the sequence number assigned to the given file name.
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.numbers.get(name).copied()
+    }
+
+    /// Assigns the next free sequence number (one above the highest, but at least the
+    /// start) to the given file name.
+    pub fn assign(&mut self, name: &str) -> usize {
+        let index = self
+            .numbers
+            .values()
+            .max()
+            .map_or(self.start, |max| self.start.max(max + 1));
+        self.numbers.insert(name.to_string(), index);
+        index
+    }
+
+    /// Moves the numbers of renamed files from their old to their new file names, so
+    /// the files keep their numbers in the next run. Names of files swapping their
+    /// names (e.g. `1.jpg` → `2.jpg` and `2.jpg` → `1.jpg`) are moved correctly.
+    pub fn rename<'a>(&mut self, renamed: impl IntoIterator<Item = (&'a Path, &'a PathBuf)>) {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        };
+        let moved = renamed
+            .into_iter()
+            .filter_map(|(from, to)| {
+                let index = self.numbers.remove(&file_name(from)?)?;
+                Some((file_name(to)?, index))
+            })
+            .collect::<Vec<_>>();
+        self.numbers.extend(moved);
+    }
+
+    /// Returns the highest assigned sequence number, or 0 if the map is empty.
+    pub fn max(&self) -> usize {
+        self.numbers.values().copied().max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_write() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("index.txt");
+        assert_eq!(StableIndex::read(&path)?, StableIndex::default());
+
+        let mut index = StableIndex::default();
+        assert_eq!(index.assign("b.jpg"), 1);
+        assert_eq!(index.assign("a.jpg"), 2);
+        index.write(&path)?;
+        assert_eq!(fs::read_to_string(&path)?, "1\tb.jpg\n2\ta.jpg\n");
+        assert_eq!(StableIndex::read(&path)?, index);
+
+        fs::write(&path, "1 b.jpg\n")?;
+        assert!(StableIndex::read(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rename() {
+        let mut index = StableIndex::default();
+        index.assign("a.jpg");
+        index.assign("b.jpg");
+        index.assign("c.jpg");
+        let path = |name: &str| PathBuf::from("dir").join(name);
+        let renamed = [
+            (path("a.jpg"), path("b.jpg")),
+            (path("b.jpg"), path("a.jpg")),
+            (path("c.jpg"), path("p 3.jpg")),
+        ];
+        index.rename(renamed.iter().map(|(from, to)| (from.as_path(), to)));
+        assert_eq!(index.get("b.jpg"), Some(1));
+        assert_eq!(index.get("a.jpg"), Some(2));
+        assert_eq!(index.get("p 3.jpg"), Some(3));
+        assert_eq!(index.get("c.jpg"), None);
+    }
+
+    #[test]
+    fn assign_with_start() {
+        let mut index = StableIndex::default().with_start(0);
+        assert_eq!(index.assign("a.jpg"), 0);
+        assert_eq!(index.assign("b.jpg"), 1);
+
+        let mut index = StableIndex::default().with_start(51);
+        assert_eq!(index.assign("a.jpg"), 51);
+        assert_eq!(index.assign("b.jpg"), 52);
+        assert_eq!(index.max(), 52);
+
+        // Numbers of a previous run above the start are continued
+        let mut index = StableIndex::default();
+        index.assign("a.jpg");
+        index.assign("b.jpg");
+        let mut index = index.with_start(2);
+        assert_eq!(index.assign("c.jpg"), 3);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::file::File;
+use crate::files::{self, RenamedFile, RenamedFiles};
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+/// Maximum length of a single file name on all supported filesystems.
+const MAX_NAME_LENGTH: usize = 255;
+
+/// Characters that are not allowed in file names on Windows filesystems.
+const WINDOWS_ILLEGAL: &[char] = &['"', '*', '/', ':', '<', '>', '?', '\\', '|'];
+
+/// Filesystem whose file name restrictions generated names have to follow.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetFs {
+    /// Linux ext4, names are limited to 255 bytes
+    Ext4,
+    /// Windows NTFS, names are limited to 255 UTF-16 units
+    Ntfs,
+    /// exFAT used on memory cards, same restrictions as NTFS
+    Exfat,
+    /// FAT32 with long file names, same restrictions as NTFS
+    Fat32,
+}
+
+impl TargetFs {
+    /// Returns `true` if the character cannot be part of a file name.
+    fn is_illegal(&self, c: char) -> bool {
+        match self {
+            TargetFs::Ext4 => c == '/' || c == '\0',
+            TargetFs::Ntfs | TargetFs::Exfat | TargetFs::Fat32 => {
+                c.is_control() || WINDOWS_ILLEGAL.contains(&c)
+            }
+        }
+    }
+
+    /// Length of the name in the units the filesystem limits.
+    fn length(&self, name: &str) -> usize {
+        match self {
+            TargetFs::Ext4 => name.len(),
+            TargetFs::Ntfs | TargetFs::Exfat | TargetFs::Fat32 => name.encode_utf16().count(),
+        }
+    }
+
+    /// Returns `true` if names must not end with a dot or a space.
+    fn forbids_trailing_dot(&self) -> bool {
+        !matches!(self, TargetFs::Ext4)
+    }
+
+    /// Checks that the file name is valid on this filesystem.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first violated restriction.
+    pub fn check(&self, name: &str) -> Result<()> {
+        if let Some(c) = name.chars().find(|c| self.is_illegal(*c)) {
+            return Err(anyhow!("Character {c:?} is not allowed on {self:?}"));
+        }
+        if self.forbids_trailing_dot() && name.ends_with(['.', ' ']) {
+            return Err(anyhow!(
+                "Names ending with dot or space are not allowed on {self:?}"
+            ));
+        }
+        let length = self.length(name);
+        if length > MAX_NAME_LENGTH {
+            return Err(anyhow!(
+                "Name is too long for {self:?} ({length} > {MAX_NAME_LENGTH})"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Makes the file name valid on this filesystem.
+    ///
+    /// Illegal characters are replaced by `_`, trailing dots and spaces are removed
+    /// and too long names are truncated, keeping the extension intact.
+    pub fn sanitize(&self, name: &str) -> String {
+        let name = name
+            .chars()
+            .map(|c| if self.is_illegal(c) { '_' } else { c })
+            .collect::<String>();
+        let name = if self.forbids_trailing_dot() {
+            name.trim_end_matches(['.', ' ']).to_string()
+        } else {
+            name
+        };
+        if self.length(&name) <= MAX_NAME_LENGTH {
+            return name;
+        }
+        let (stem, extension) = match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+            _ => (name.as_str(), String::new()),
+        };
+        let mut stem = stem.to_string();
+        while !stem.is_empty() && self.length(&stem) + self.length(&extension) > MAX_NAME_LENGTH {
+            stem.pop();
+        }
+        stem + &extension
+    }
+}
+
+/// Filesystem restrictions applied to generated names, together with how to handle violations.
+#[derive(Debug, Clone, Copy)]
+pub struct NamePolicy {
+    pub target: TargetFs,
+    /// Fix invalid names instead of returning an error
+    pub fix: bool,
+}
+
+impl NamePolicy {
+    /// Validates or fixes the file name of the given path according to the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is not valid UTF-8, or if it violates the
+    /// restrictions and fixing is not enabled.
+    pub fn apply(&self, path: PathBuf) -> Result<PathBuf> {
+        let Some(name) = path.file_name() else {
+            return Ok(path);
+        };
+        let name = name
+            .to_str()
+            .ok_or(anyhow!("Non UTF-8 file name {path:?}"))?;
+        if self.fix {
+            Ok(path.with_file_name(self.target.sanitize(name)))
+        } else {
+            self.target
+                .check(name)
+                .context(format!("Invalid name {:?}", Path::new(name)))?;
+            Ok(path)
+        }
+    }
+
+    /// Applies the policy to every new path of a rename plan, see [`NamePolicy::apply`].
+    ///
+    /// # Returns
+    ///
+    /// The plan with the checked or fixed paths, and the files left out of it because
+    /// their new name is not valid UTF-8 and cannot be checked (e.g. a non UTF-8
+    /// extension kept by the name template). These files keep their names.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy fails for any new path, or if fixed names collide
+    /// (e.g. two long names truncated to the same one), see [`files::check_collisions`].
+    pub fn apply_all<'a>(
+        &self,
+        renamed: RenamedFiles<'a>,
+    ) -> Result<(RenamedFiles<'a>, Vec<&'a File>)> {
+        let (valid, skipped): (Vec<_>, Vec<_>) =
+            renamed.into_iter().partition(|RenamedFile(_, new_path)| {
+                new_path
+                    .file_name()
+                    .is_none_or(|name| name.to_str().is_some())
+            });
+        let renamed = valid
+            .into_iter()
+            .map(|RenamedFile(file, new_path)| Ok(RenamedFile(file, self.apply(new_path)?)))
+            .collect::<Result<Vec<_>>>()?;
+        files::check_collisions(&renamed)?;
+        let skipped = skipped
+            .into_iter()
+            .map(|RenamedFile(file, _)| file)
+            .collect();
+        Ok((renamed, skipped))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check() {
+        assert!(TargetFs::Ext4.check("2025-05-01 Trip: Paris").is_ok());
+        assert!(TargetFs::Fat32.check("2025-05-01 Trip: Paris").is_err());
+        assert!(TargetFs::Ntfs.check("what?.jpg").is_err());
+        assert!(TargetFs::Exfat.check("trailing.").is_err());
+        assert!(TargetFs::Ext4.check("trailing.").is_ok());
+
+        // Four bytes in UTF-8, two UTF-16 units
+        let emoji = "\u{1F4F7}".repeat(100);
+        assert!(TargetFs::Ext4.check(&emoji).is_err());
+        assert!(TargetFs::Ntfs.check(&emoji).is_ok());
+        assert!(TargetFs::Ntfs.check(&"a".repeat(256)).is_err());
+    }
+
+    #[test]
+    fn sanitize() {
+        assert_eq!(
+            TargetFs::Fat32.sanitize("2025-05-01 Trip: Paris"),
+            "2025-05-01 Trip_ Paris"
+        );
+        assert_eq!(
+            TargetFs::Ext4.sanitize("2025-05-01 Trip: Paris"),
+            "2025-05-01 Trip: Paris"
+        );
+        assert_eq!(TargetFs::Ntfs.sanitize("name. "), "name");
+
+        let long = format!("{}.jpg", "a".repeat(300));
+        let sanitized = TargetFs::Ext4.sanitize(&long);
+        assert_eq!(sanitized.len(), MAX_NAME_LENGTH);
+        assert!(sanitized.ends_with("a.jpg"));
+    }
+
+    #[test]
+    fn apply() -> Result<()> {
+        let path = PathBuf::from("./photos/Trip: Paris 1.jpg");
+        let policy = NamePolicy {
+            target: TargetFs::Fat32,
+            fix: false,
+        };
+        assert!(policy.apply(path.clone()).is_err());
+
+        let policy = NamePolicy {
+            target: TargetFs::Fat32,
+            fix: true,
+        };
+        assert_eq!(
+            policy.apply(path.clone())?,
+            PathBuf::from("./photos/Trip_ Paris 1.jpg")
+        );
+
+        let policy = NamePolicy {
+            target: TargetFs::Ext4,
+            fix: false,
+        };
+        assert_eq!(policy.apply(path.clone())?, path);
+        Ok(())
+    }
+}
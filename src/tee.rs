@@ -0,0 +1,66 @@
+use std::io::{self, Write};
+
+/// Writer that duplicates everything written to it into an optional second writer.
+///
+/// The primary writer is always written to, the secondary one only when present.
+/// This is used to copy the error stream into a log file while keeping the
+/// terminal output intact.
+pub struct Tee<A, B> {
+    primary: A,
+    secondary: Option<B>,
+}
+
+impl<A, B> Tee<A, B> {
+    /// Creates a new writer forwarding to `primary` and, if set, to `secondary`.
+    pub fn new(primary: A, secondary: Option<B>) -> Self {
+        Self { primary, secondary }
+    }
+
+    /// Returns the secondary writer, to write what should not reach the primary one.
+    pub fn secondary_mut(&mut self) -> Option<&mut B> {
+        self.secondary.as_mut()
+    }
+}
+
+impl<A, B> Write for Tee<A, B>
+where
+    A: Write,
+    B: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.primary.write(buf)?;
+        if let Some(secondary) = &mut self.secondary {
+            secondary.write_all(&buf[..written])?;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.primary.flush()?;
+        if let Some(secondary) = &mut self.secondary {
+            secondary.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write() -> io::Result<()> {
+        let mut primary = Vec::new();
+        let mut secondary = Vec::new();
+        let mut tee = Tee::new(&mut primary, Some(&mut secondary));
+        writeln!(tee, "warning")?;
+        assert_eq!(primary, b"warning\n");
+        assert_eq!(secondary, b"warning\n");
+
+        let mut primary = Vec::new();
+        let mut tee = Tee::new(&mut primary, None::<Vec<u8>>);
+        writeln!(tee, "warning")?;
+        assert_eq!(primary, b"warning\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+//! Helpers shared by unit tests for building photo fixtures on disk.
+
+use exif::{experimental::Writer, Field, In, Tag, Value};
+use std::{fs, io::Cursor, path::Path};
+
+/// Builds an EXIF field of the primary image.
+fn field(tag: Tag, value: Value) -> Field {
+    Field {
+        tag,
+        ifd_num: In::PRIMARY,
+        value,
+    }
+}
+
+/// Builds TIFF encoded EXIF data with the given fields.
+fn tiff(fields: &[Field]) -> Vec<u8> {
+    let mut writer = Writer::new();
+    fields.iter().for_each(|f| writer.push_field(f));
+    let mut tiff = Cursor::new(Vec::new());
+    writer.write(&mut tiff, false).unwrap();
+    tiff.into_inner()
+}
+
+/// Builds TIFF encoded EXIF data with the given ASCII fields.
+fn exif_tiff(fields: &[(Tag, &str)]) -> Vec<u8> {
+    let fields = fields
+        .iter()
+        .map(|(tag, value)| field(*tag, Value::Ascii(vec![value.as_bytes().to_vec()])))
+        .collect::<Vec<_>>();
+    tiff(&fields)
+}
+
+/// Builds a PDF document with the given `CreationDate` in its information dictionary.
+#[cfg(feature = "pdf")]
+pub fn pdf(creation_date: Option<&str>) -> Vec<u8> {
+    use lopdf::{dictionary, Document, Object};
+
+    let mut document = Document::with_version("1.5");
+    let info = match creation_date {
+        Some(date) => dictionary! { "CreationDate" => Object::string_literal(date) },
+        None => dictionary! {},
+    };
+    let info = document.add_object(info);
+    document.trailer.set("Info", info);
+    let mut data = Vec::new();
+    document.save_to(&mut data).unwrap();
+    data
+}
+
+/// Builds a minimal JPEG file containing an EXIF segment with the given ASCII fields.
+///
+/// The image has no scan data, only the markers needed for the EXIF reader
+/// to locate the APP1 segment.
+pub fn exif_jpeg(fields: &[(Tag, &str)]) -> Vec<u8> {
+    jpeg(&exif_tiff(fields))
+}
+
+/// Builds a minimal JPEG file containing the given TIFF encoded EXIF data.
+fn jpeg(tiff: &[u8]) -> Vec<u8> {
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    jpeg.extend_from_slice(b"Exif\0\0");
+    jpeg.extend_from_slice(tiff);
+    jpeg.extend_from_slice(&[0xFF, 0xD9]);
+    jpeg
+}
+
+/// Builds a WebP (RIFF) file with a single chunk of the given type, without image data.
+fn webp(fourcc: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let padding = payload.len() % 2;
+    let mut webp = b"RIFF".to_vec();
+    webp.extend_from_slice(&((payload.len() + padding + 12) as u32).to_le_bytes());
+    webp.extend_from_slice(b"WEBP");
+    webp.extend_from_slice(fourcc);
+    webp.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    webp.extend_from_slice(payload);
+    webp.resize(webp.len() + padding, 0);
+    webp
+}
+
+/// Builds a WebP file with an `EXIF` chunk containing the given ASCII fields.
+pub fn exif_webp(fields: &[(Tag, &str)]) -> Vec<u8> {
+    webp(b"EXIF", &exif_tiff(fields))
+}
+
+/// Builds a WebP file with an `XMP ` chunk containing the given packet.
+pub fn xmp_webp(xmp: &str) -> Vec<u8> {
+    webp(b"XMP ", xmp.as_bytes())
+}
+
+/// Builds a GIF file with the XMP application extension containing the given packet.
+///
+/// Only the header and the extension are present, without image data.
+pub fn xmp_gif(xmp: &str) -> Vec<u8> {
+    let mut gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00".to_vec();
+    gif.extend_from_slice(b"\x21\xFF\x0BXMP DataXMP");
+    gif.extend_from_slice(xmp.as_bytes());
+    // Magic trailer making the raw packet valid GIF sub-blocks
+    gif.push(0x01);
+    gif.extend((0..=0xFF).rev());
+    gif.extend_from_slice(&[0x00, 0x3B]);
+    gif
+}
+
+/// Builds an ISO base media file format box with the given type and body.
+fn iso_box(boxtype: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut iso_box = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    iso_box.extend_from_slice(boxtype);
+    iso_box.extend_from_slice(body);
+    iso_box
+}
+
+/// Builds a HEIF (HEIC) file whose only item is EXIF data with the given ASCII fields.
+///
+/// The EXIF item is stored inside the meta box (`idat`), there is no image item.
+pub fn exif_heic(fields: &[(Tag, &str)]) -> Vec<u8> {
+    heif(b"heic\0\0\0\0mif1heic", fields)
+}
+
+/// Builds an AVIF file like [`exif_heic`], differing only in the brands of the file.
+pub fn exif_avif(fields: &[(Tag, &str)]) -> Vec<u8> {
+    heif(b"avif\0\0\0\0mif1avif", fields)
+}
+
+/// Builds an ISO base media file with the given `ftyp` box body, whose only item
+/// is EXIF data with the given ASCII fields.
+fn heif(ftyp: &[u8], fields: &[(Tag, &str)]) -> Vec<u8> {
+    // Offset of the TIFF header within the EXIF item
+    let mut exif = 0u32.to_be_bytes().to_vec();
+    exif.extend_from_slice(&exif_tiff(fields));
+
+    // Item 1 of type Exif (version 2 entry)
+    let infe = iso_box(
+        b"infe",
+        &[&[2, 0, 0, 0, 0, 1, 0, 0][..], b"Exif\0"].concat(),
+    );
+    let iinf = iso_box(b"iinf", &[&[0, 0, 0, 0, 0, 1][..], &infe].concat());
+    // Version 1, 4 byte offsets and lengths, one item with one extent in idat
+    let mut iloc = vec![1, 0, 0, 0, 0x44, 0x00, 0, 1, 0, 1, 0, 1, 0, 0, 0, 1];
+    iloc.extend_from_slice(&0u32.to_be_bytes());
+    iloc.extend_from_slice(&(exif.len() as u32).to_be_bytes());
+    let meta = [
+        &[0, 0, 0, 0][..],
+        &iinf,
+        &iso_box(b"iloc", &iloc),
+        &iso_box(b"idat", &exif),
+    ]
+    .concat();
+
+    [iso_box(b"ftyp", ftyp), iso_box(b"meta", &meta)].concat()
+}
+
+/// Writes a JPEG fixture with `DateTimeOriginal` set to `created`
+/// (formatted as `%Y:%m:%d %H:%M:%S`), creating parent directories as needed.
+pub fn write_photo(path: impl AsRef<Path>, created: &str) {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, exif_jpeg(&[(Tag::DateTimeOriginal, created)])).unwrap();
+}
+
+/// Writes a JPEG fixture like [`write_photo`], with the GPS position given by
+/// `latitude` and `longitude` in degrees.
+pub fn write_photo_at(path: impl AsRef<Path>, created: &str, latitude: f64, longitude: f64) {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    let degrees = |degrees: f64| {
+        Value::Rational(vec![
+            ((degrees.abs() * 1e6).round() as u32, 1_000_000).into(),
+            (0, 1).into(),
+            (0, 1).into(),
+        ])
+    };
+    let reference = |degrees: f64, positive: &str, negative: &str| {
+        let reference = if degrees < 0.0 { negative } else { positive };
+        Value::Ascii(vec![reference.as_bytes().to_vec()])
+    };
+    let fields = [
+        field(
+            Tag::DateTimeOriginal,
+            Value::Ascii(vec![created.as_bytes().to_vec()]),
+        ),
+        field(Tag::GPSLatitude, degrees(latitude)),
+        field(Tag::GPSLatitudeRef, reference(latitude, "N", "S")),
+        field(Tag::GPSLongitude, degrees(longitude)),
+        field(Tag::GPSLongitudeRef, reference(longitude, "E", "W")),
+    ];
+    fs::write(path, jpeg(&tiff(&fields))).unwrap();
+}
@@ -0,0 +1,94 @@
+use crate::files::RenamedFile;
+use std::{collections::BTreeMap, path::Path};
+
+/// Indentation of file names under their destination directory.
+const INDENT: &str = "  ";
+
+/// Renders the directories a move plan puts files into, one line per directory with
+/// the number of files moved into it, e.g. the day directories of `move-by-days`.
+///
+/// # Arguments
+///
+/// * `plan` - Planned moves, see [`crate::files::Files::move_by_days`]
+/// * `root` - Directory the destinations are shown relative to, destinations outside
+///   of it are shown in full
+/// * `names` - List the new file names, indented, under each directory
+///
+/// # Examples
+///
+/// ```text
+/// 2025-05-01/ (2 files)
+///   IMG_001.jpg
+///   IMG_002.jpg
+/// 2025-05-03/ (1 file)
+///   IMG_003.jpg
+/// ```
+pub fn render_plan(plan: &[RenamedFile], root: &Path, names: bool) -> String {
+    let directories = plan
+        .iter()
+        .filter_map(|RenamedFile(_, new_path)| Some((new_path.parent()?, new_path.file_name()?)))
+        .fold(
+            BTreeMap::<_, Vec<_>>::new(),
+            |mut directories, (parent, name)| {
+                directories.entry(parent).or_default().push(name);
+                directories
+            },
+        );
+    let mut ret = String::new();
+    for (directory, files) in directories {
+        ret += &format!(
+            "{}/ ({} {})\n",
+            directory.strip_prefix(root).unwrap_or(directory).display(),
+            files.len(),
+            if files.len() == 1 { "file" } else { "files" }
+        );
+        if names {
+            files
+                .iter()
+                .for_each(|name| ret += &format!("{INDENT}{}\n", name.to_string_lossy()));
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{DateSource, File};
+    use std::path::PathBuf;
+
+    #[test]
+    fn render_plan() {
+        let file = |path: &str| File {
+            path: PathBuf::from(path),
+            created: "2025-05-01T10:00:00".parse().unwrap(),
+            source: DateSource::Exif,
+            offset: None,
+        };
+        let files = [
+            file("/photos/a/IMG_001.jpg"),
+            file("/photos/IMG_002.jpg"),
+            file("/photos/IMG_003.jpg"),
+        ];
+        let plan = [
+            RenamedFile(&files[0], PathBuf::from("/photos/a/2025-05-01/IMG_001.jpg")),
+            RenamedFile(&files[1], PathBuf::from("/photos/2025-05-01/IMG_002.jpg")),
+            RenamedFile(&files[2], PathBuf::from("/photos/2025-05-03/IMG_003.jpg")),
+        ];
+        let root = Path::new("/photos");
+
+        assert_eq!(
+            super::render_plan(&plan, root, false),
+            "2025-05-01/ (1 file)\n2025-05-03/ (1 file)\na/2025-05-01/ (1 file)\n"
+        );
+        assert_eq!(
+            super::render_plan(&plan[1..], root, true),
+            "2025-05-01/ (1 file)\n  IMG_002.jpg\n2025-05-03/ (1 file)\n  IMG_003.jpg\n"
+        );
+        assert_eq!(
+            super::render_plan(&plan[2..], Path::new("/sorted"), false),
+            "/photos/2025-05-03/ (1 file)\n"
+        );
+        assert_eq!(super::render_plan(&[], root, true), "");
+    }
+}
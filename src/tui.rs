@@ -0,0 +1,160 @@
+use crate::files::RenamedFile;
+use anyhow::Result;
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    style::{Modifier, Style},
+    widgets::{Block, List, ListState},
+    DefaultTerminal,
+};
+
+/// Key bindings shown in the title of the plan list.
+const HELP: &str = " Space: toggle, a: toggle all, Enter: apply, q: cancel ";
+
+/// Operations of a plan approved by the user, independent of rendering.
+#[derive(Debug)]
+struct Review {
+    /// Whether the operation at the same index of the plan is approved
+    approved: Vec<bool>,
+    /// Index of the highlighted operation
+    cursor: usize,
+}
+
+impl Review {
+    /// Creates a review of a plan with `len` operations, all of them approved.
+    fn new(len: usize) -> Self {
+        Self {
+            approved: vec![true; len],
+            cursor: 0,
+        }
+    }
+
+    /// Moves the cursor to the next operation, stopping at the last one.
+    fn next(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.approved.len().saturating_sub(1));
+    }
+
+    /// Moves the cursor to the previous operation, stopping at the first one.
+    fn previous(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Approves or rejects the highlighted operation.
+    fn toggle(&mut self) {
+        if let Some(approved) = self.approved.get_mut(self.cursor) {
+            *approved = !*approved;
+        }
+    }
+
+    /// Rejects all operations if all of them are approved, otherwise approves all of them.
+    fn toggle_all(&mut self) {
+        let approve = !self.approved.iter().all(|approved| *approved);
+        self.approved
+            .iter_mut()
+            .for_each(|approved| *approved = approve);
+    }
+
+    /// Keeps only the approved operations of `plan`, preserving their order.
+    fn filter<T>(&self, plan: Vec<T>) -> Vec<T> {
+        plan.into_iter()
+            .zip(&self.approved)
+            .filter_map(|(operation, approved)| approved.then_some(operation))
+            .collect()
+    }
+}
+
+/// Lets the user review `plan` in a terminal UI and toggle individual operations off.
+///
+/// # Returns
+///
+/// The approved operations when the plan is confirmed, or an empty plan when
+/// the review is cancelled.
+///
+/// # Errors
+///
+/// Returns an error if the terminal cannot be drawn or read.
+pub fn review(plan: Vec<RenamedFile>) -> Result<Vec<RenamedFile>> {
+    let mut terminal = ratatui::init();
+    let confirmed = run(&mut terminal, &plan);
+    ratatui::restore();
+    Ok(match confirmed? {
+        Some(review) => review.filter(plan),
+        None => Vec::new(),
+    })
+}
+
+/// Draws the plan and handles keys until the user confirms or cancels the review.
+fn run(terminal: &mut DefaultTerminal, plan: &[RenamedFile]) -> Result<Option<Review>> {
+    let mut review = Review::new(plan.len());
+    let mut state = ListState::default();
+    loop {
+        state.select(Some(review.cursor));
+        terminal.draw(|frame| {
+            let items =
+                plan.iter()
+                    .zip(&review.approved)
+                    .map(|(RenamedFile(file, new_path), approved)| {
+                        let mark = if *approved { 'x' } else { ' ' };
+                        format!("[{mark}] {:?} => {:?}", file.path, new_path)
+                    });
+            let list = List::new(items)
+                .block(Block::bordered().title(HELP))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+            frame.render_stateful_widget(list, frame.area(), &mut state);
+        })?;
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Down | KeyCode::Char('j') => review.next(),
+                KeyCode::Up | KeyCode::Char('k') => review.previous(),
+                KeyCode::Char(' ') => review.toggle(),
+                KeyCode::Char('a') => review.toggle_all(),
+                KeyCode::Enter => return Ok(Some(review)),
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_approved() {
+        let plan = vec!["a", "b", "c", "d"];
+        let mut review = Review::new(plan.len());
+        assert_eq!(review.filter(plan.clone()), plan);
+
+        review.toggle();
+        review.next();
+        review.next();
+        review.toggle();
+        assert_eq!(review.filter(plan.clone()), ["b", "d"]);
+
+        // Toggling again approves the operation back
+        review.toggle();
+        assert_eq!(review.filter(plan.clone()), ["b", "c", "d"]);
+
+        // Cursor stays within the plan
+        (0..10).for_each(|_| review.next());
+        review.toggle();
+        assert_eq!(review.filter(plan.clone()), ["b", "c"]);
+        (0..10).for_each(|_| review.previous());
+        assert_eq!(review.cursor, 0);
+
+        review.toggle_all();
+        assert_eq!(review.filter(plan.clone()), plan);
+        review.toggle_all();
+        assert_eq!(review.filter(plan), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn empty_plan() {
+        let mut review = Review::new(0);
+        review.next();
+        review.toggle();
+        assert_eq!(review.cursor, 0);
+        assert_eq!(review.filter(Vec::<&str>::new()), Vec::<&str>::new());
+    }
+}
@@ -0,0 +1,224 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+/// A single rename applied to the filesystem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Log of applied renames that allows reverting them with [`undo`].
+///
+/// The log is a JSON lines file with one [`Entry`] per line. Entries are
+/// appended right after each rename, so the log stays usable even if the run
+/// is interrupted. Paths are stored as absolute paths.
+pub struct UndoLog(fs::File);
+
+impl UndoLog {
+    /// Opens the log for appending, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        fs::File::options()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(Self)
+            .context(format!("Cannot open undo log {path:?}"))
+    }
+
+    /// Appends a rename that was just applied.
+    pub fn record(&mut self, from: &Path, to: &Path) -> Result<()> {
+        let entry = Entry {
+            from: std::path::absolute(from)?,
+            to: std::path::absolute(to)?,
+        };
+        writeln!(self.0, "{}", serde_json::to_string(&entry)?)?;
+        Ok(self.0.flush()?)
+    }
+}
+
+/// Reads all entries of the log, in the order they were applied.
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<Entry>> {
+    let path = path.as_ref();
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect::<Result<_>>()
+        .context(format!("Invalid undo log {path:?}"))
+}
+
+/// Reads a plan written by `--dry-run-json`, a JSON array of entries.
+pub fn read_plan(path: impl AsRef<Path>) -> Result<Vec<Entry>> {
+    let path = path.as_ref();
+    serde_json::from_str(&fs::read_to_string(path)?).context(format!("Invalid plan {path:?}"))
+}
+
+/// Checks that a plan can be applied: the original path of every entry must
+/// still exist and its new path must be free and not shared with another entry.
+///
+/// # Errors
+///
+/// Returns an error describing the first conflict.
+pub fn check(plan: &[Entry]) -> Result<()> {
+    let mut targets = HashSet::new();
+    plan.iter().try_for_each(|Entry { from, to }| {
+        if !from.exists() {
+            return Err(anyhow!(
+                "{from:?} is no longer there, cannot move it to {to:?}"
+            ));
+        }
+        if to.exists() {
+            return Err(anyhow!("Cannot move {from:?}, {to:?} already exists"));
+        }
+        if !targets.insert(to) {
+            return Err(anyhow!("Cannot move {from:?}, {to:?} is planned twice"));
+        }
+        Ok(())
+    })
+}
+
+/// Applies renames of a plan, e.g. read by [`read_plan`], recording them in `log`.
+///
+/// Every entry is checked by [`check`] before anything is renamed.
+/// Missing parent directories of new paths are created.
+///
+/// # Errors
+///
+/// Returns an error on the first conflict, without renaming anything, or on
+/// the first rename that fails.
+pub fn apply(plan: &[Entry], mut log: Option<&mut UndoLog>) -> Result<()> {
+    check(plan)?;
+    plan.iter().try_for_each(|Entry { from, to }| {
+        if let Some(parent) = to.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(from, to)?;
+        match &mut log {
+            Some(log) => log.record(from, to),
+            None => Ok(()),
+        }
+    })
+}
+
+/// Reverts renames recorded in the log, starting with the last one.
+///
+/// Before moving a file back, checks that it is still at its new path and that
+/// the original path is free. Directories created for moved files are kept.
+/// The log is removed once every rename has been reverted.
+///
+/// # Returns
+///
+/// The reverted entries, in the order they were reverted.
+///
+/// # Errors
+///
+/// Returns an error on the first entry that cannot be reverted. Entries
+/// reverted before it stay reverted and the log is kept.
+pub fn undo(path: impl AsRef<Path>) -> Result<Vec<Entry>> {
+    let path = path.as_ref();
+    let mut entries = read(path)?;
+    entries.reverse();
+    entries.iter().try_for_each(|Entry { from, to }| {
+        if !to.exists() {
+            return Err(anyhow!(
+                "{to:?} is no longer there, cannot move it back to {from:?}"
+            ));
+        }
+        if from.exists() {
+            return Err(anyhow!("Cannot move {to:?} back, {from:?} already exists"));
+        }
+        Ok(fs::rename(to, from)?)
+    })?;
+    fs::remove_file(path)?;
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_and_undo() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        let log_path = path("undo.jsonl");
+        fs::write(path("a.jpg"), "a")?;
+        fs::write(path("b.jpg"), "b")?;
+
+        // Swap the files through a temporary name
+        let mut log = UndoLog::open(&log_path)?;
+        [("a.jpg", "tmp"), ("b.jpg", "a.jpg"), ("tmp", "b.jpg")]
+            .iter()
+            .try_for_each(|(from, to)| {
+                fs::rename(path(from), path(to))?;
+                log.record(&path(from), &path(to))
+            })?;
+        assert_eq!(read(&log_path)?.len(), 3);
+        assert_eq!(fs::read_to_string(path("a.jpg"))?, "b");
+
+        let reverted = undo(&log_path)?;
+        assert_eq!(reverted.first().map(|e| &e.to), Some(&path("b.jpg")));
+        assert_eq!(fs::read_to_string(path("a.jpg"))?, "a");
+        assert_eq!(fs::read_to_string(path("b.jpg"))?, "b");
+        assert!(!log_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn apply_plan() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        let entry = |from: &str, to: &str| Entry {
+            from: path(from),
+            to: path(to),
+        };
+        fs::write(path("a.jpg"), "a")?;
+        fs::write(path("b.jpg"), "b")?;
+        fs::write(
+            path("plan.json"),
+            serde_json::to_string(&[entry("a.jpg", "x/c.jpg")])?,
+        )?;
+        assert_eq!(read_plan(path("plan.json"))?, [entry("a.jpg", "x/c.jpg")]);
+
+        // Conflicts abort the whole batch
+        let conflicts = [
+            vec![entry("a.jpg", "c.jpg"), entry("missing.jpg", "d.jpg")],
+            vec![entry("a.jpg", "c.jpg"), entry("b.jpg", "a.jpg")],
+            vec![entry("a.jpg", "c.jpg"), entry("b.jpg", "c.jpg")],
+        ];
+        for plan in conflicts {
+            assert!(apply(&plan, None).is_err());
+            assert!(path("a.jpg").exists());
+            assert!(!path("c.jpg").exists());
+        }
+
+        let mut log = UndoLog::open(path("undo.jsonl"))?;
+        apply(&read_plan(path("plan.json"))?, Some(&mut log))?;
+        assert_eq!(fs::read_to_string(path("x/c.jpg"))?, "a");
+        assert_eq!(read(path("undo.jsonl"))?, [entry("a.jpg", "x/c.jpg")]);
+        Ok(())
+    }
+
+    #[test]
+    fn undo_moved_file() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = |name: &str| dir.path().join(name);
+        let log_path = path("undo.jsonl");
+        fs::write(path("a.jpg"), "a")?;
+        fs::rename(path("a.jpg"), path("c.jpg"))?;
+        UndoLog::open(&log_path)?.record(&path("a.jpg"), &path("c.jpg"))?;
+
+        fs::remove_file(path("c.jpg"))?;
+        assert!(undo(&log_path).is_err());
+        assert!(log_path.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::NaiveDateTime;
+
+/// File extensions of containers searched for XMP metadata when they have no EXIF date.
+pub const EXTENSIONS: [&str; 2] = ["webp", "gif"];
+
+/// XMP properties holding the creation date, in order of preference.
+const DATE_PROPERTIES: [&str; 3] = [
+    "exif:DateTimeOriginal",
+    "xmp:CreateDate",
+    "photoshop:DateCreated",
+];
+
+/// Application identifier and authentication code of the GIF extension holding XMP.
+const GIF_XMP_APPLICATION: &[u8] = b"XMP DataXMP";
+
+/// Closing tag of the XMP packet.
+const XMP_END: &[u8] = b"</x:xmpmeta>";
+
+/// Returns the position of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// Returns the payload of the `XMP ` chunk of a WebP (RIFF) file.
+fn webp_packet(data: &[u8]) -> Option<&[u8]> {
+    if data.get(0..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    let mut chunks = data.get(12..)?;
+    while chunks.len() >= 8 {
+        let size = u32::from_le_bytes(chunks[4..8].try_into().ok()?) as usize;
+        if &chunks[0..4] == b"XMP " {
+            return chunks.get(8..8 + size);
+        }
+        // Chunks are padded to an even size
+        chunks = chunks.get(8 + size + size % 2..).unwrap_or_default();
+    }
+    None
+}
+
+/// Returns the XMP packet stored in the application extension of a GIF file.
+///
+/// The packet is stored as raw bytes after the application identifier, so it
+/// can be read up to its closing tag without decoding GIF sub-blocks.
+fn gif_packet(data: &[u8]) -> Option<&[u8]> {
+    if !data.starts_with(b"GIF8") {
+        return None;
+    }
+    let data = &data[find(data, GIF_XMP_APPLICATION)? + GIF_XMP_APPLICATION.len()..];
+    Some(&data[..find(data, XMP_END)? + XMP_END.len()])
+}
+
+/// Returns the value of an XMP property written either as an attribute or as an element.
+fn property<'a>(xmp: &'a str, name: &str) -> Option<&'a str> {
+    let attribute = format!("{name}=\"");
+    let element = format!("<{name}>");
+    if let Some((_, rest)) = xmp.split_once(&attribute) {
+        rest.split_once('"').map(|(value, _)| value)
+    } else {
+        let (_, rest) = xmp.split_once(&element)?;
+        rest.split_once('<').map(|(value, _)| value.trim())
+    }
+}
+
+/// Extracts the creation date from an XMP packet.
+///
+/// Fractions of seconds and time zone offsets following the time are ignored.
+fn parse_date(xmp: &str) -> Option<NaiveDateTime> {
+    DATE_PROPERTIES.iter().find_map(|name| {
+        NaiveDateTime::parse_and_remainder(property(xmp, name)?, "%Y-%m-%dT%H:%M:%S")
+            .ok()
+            .map(|(created, _)| created)
+    })
+}
+
+/// Reads the creation date from XMP metadata embedded in a WebP or GIF file.
+///
+/// Returns `None` if the data is not one of these containers, has no XMP packet
+/// or the packet contains no valid creation date.
+pub fn read(data: &[u8]) -> Option<NaiveDateTime> {
+    let packet = webp_packet(data).or_else(|| gif_packet(data))?;
+    parse_date(std::str::from_utf8(packet).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{xmp_gif, xmp_webp};
+    use std::str::FromStr;
+
+    #[test]
+    fn parse_date_forms() {
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14").ok();
+        assert_eq!(
+            parse_date(r#"<rdf:Description xmp:CreateDate="2025-05-01T12:13:14+02:00"/>"#),
+            created
+        );
+        assert_eq!(
+            parse_date("<exif:DateTimeOriginal>2025-05-01T12:13:14.52</exif:DateTimeOriginal>"),
+            created
+        );
+        assert_eq!(
+            parse_date(
+                r#"<rdf:Description xmp:CreateDate="2000-01-01T00:00:00"
+                    exif:DateTimeOriginal="2025-05-01T12:13:14"/>"#
+            ),
+            created
+        );
+        assert_eq!(
+            parse_date(r#"<rdf:Description xmp:CreateDate="2025"/>"#),
+            None
+        );
+    }
+
+    #[test]
+    fn read_containers() {
+        let xmp =
+            r#"<x:xmpmeta><rdf:Description xmp:CreateDate="2025-05-01T12:13:14"/></x:xmpmeta>"#;
+        let created = NaiveDateTime::from_str("2025-05-01T12:13:14").ok();
+        assert_eq!(read(&xmp_webp(xmp)), created);
+        assert_eq!(read(&xmp_gif(xmp)), created);
+        assert_eq!(read(xmp.as_bytes()), None);
+    }
+}